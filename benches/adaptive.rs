@@ -0,0 +1,51 @@
+//! Compares hits on `Memory::adaptive` with a plain, always hashing `Memory`,
+//! for caches small enough to stay in a scanned list.
+//!
+//! Run with `cargo bench --bench adaptive`.
+
+use souvenir::Memory;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const LINEAR_THRESHOLD: usize = 8;
+const LOOKUPS: u32 = 1_000_000;
+const ROUNDS: u32 = 5;
+
+fn best_of(mut lookup: impl FnMut()) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            lookup();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() {
+    println!("entries   hashed      adaptive");
+    for entries in 1..=LINEAR_THRESHOLD {
+        let keys: Vec<String> = (0..entries).map(|key| format!("key-{key}")).collect();
+        let mut hashed = Memory::new(String::len);
+        let mut adaptive = Memory::adaptive(String::len, LINEAR_THRESHOLD);
+        for key in &keys {
+            hashed.resolve(key);
+            adaptive.resolve(key);
+        }
+
+        let hashed = best_of(|| {
+            for key in keys.iter().cycle().take(LOOKUPS as usize) {
+                black_box(hashed.resolve(black_box(key)));
+            }
+        });
+        let adaptive = best_of(|| {
+            for key in keys.iter().cycle().take(LOOKUPS as usize) {
+                black_box(adaptive.resolve(black_box(key)));
+            }
+        });
+
+        println!("{entries:<9} {hashed:<11?} {adaptive:?}");
+    }
+}
@@ -0,0 +1,51 @@
+//! Compares warming a memory one key at a time with `Memory::prewarm_parallel`.
+//!
+//! Run with `cargo bench --bench prewarm`.
+
+use souvenir::Memory;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const KEYS: u64 = 512;
+const ROUNDS: u32 = 5;
+
+/// A deliberately slow resolver, spinning through a few hundred thousand steps.
+fn scramble(seed: &u64) -> u64 {
+    (0..200_000u64).fold(
+        *seed + 1,
+        |n, _| if n % 2 == 0 { n / 2 } else { 3 * n + 1 } | 1,
+    )
+}
+
+fn best_of(mut warm: impl FnMut()) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            warm();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() {
+    let sequential = best_of(|| {
+        let mut memory = Memory::new(scramble);
+        for key in 0..KEYS {
+            black_box(memory.resolve(&key));
+        }
+        black_box(memory);
+    });
+    let parallel = best_of(|| {
+        black_box(Memory::prewarm_parallel(&scramble, 0..KEYS));
+    });
+
+    println!("sequential: {sequential:?}");
+    println!("parallel:   {parallel:?}");
+    println!(
+        "speedup:    {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
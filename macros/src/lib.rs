@@ -0,0 +1,455 @@
+//! # Souvenir macros
+//!
+//! Attribute and derive macros for [souvenir](https://crates.io/crates/souvenir),
+//! re-exported from there behind the `proc-macros` feature.
+//!
+//! The input is parsed by hand rather than with `syn`, which keeps the crate
+//! free of dependencies but limits it to plain, non-generic items.
+
+#![deny(clippy::pedantic)]
+#![deny(clippy::correctness)]
+#![deny(clippy::nursery)]
+#![deny(clippy::suspicious)]
+#![deny(clippy::complexity)]
+#![warn(clippy::style)]
+
+use proc_macro::{Delimiter, Group, Ident, Spacing, TokenStream, TokenTree};
+
+/// Memoize a function taking a single argument, with a thread-local `Memory`.
+///
+/// The argument must be `Clone + Eq + Hash` and the return value `Clone`.
+/// Recursive calls go through the memory too.
+///
+/// # Examples
+/// ```
+/// use souvenir::memoize;
+///
+/// #[memoize]
+/// fn fibonacci(n: u64) -> u64 {
+///     if n < 2 { n } else { fibonacci(n - 1) + fibonacci(n - 2) }
+/// }
+///
+/// assert_eq!(fibonacci(90), 2_880_067_194_370_816_120);
+/// ```
+#[proc_macro_attribute]
+pub fn memoize(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return compile_error("`memoize` takes no arguments");
+    }
+    match ItemFn::parse(item).and_then(|function| expand_memoize(&function)) {
+        Ok(expanded) => expanded,
+        Err(message) => compile_error(message),
+    }
+}
+
+fn expand_memoize(function: &ItemFn) -> Result<TokenStream, &'static str> {
+    let Some(output) = &function.output else {
+        return Err("`memoize` needs a function returning a value");
+    };
+    let argument = Argument::parse(function.inputs.stream())?;
+    Ok(expand(
+        "
+        __PREFIX fn __NAME(__ARG: __TYPE) -> __OUTPUT {
+            fn __souvenir_inner(__PATTERN: __TYPE) -> __OUTPUT __BODY
+
+            fn __souvenir_resolve(input: &__TYPE) -> __OUTPUT {
+                __souvenir_inner(::std::clone::Clone::clone(input))
+            }
+
+            ::std::thread_local! {
+                static MEMORY: ::std::cell::RefCell<
+                    ::souvenir::Memory<fn(&__TYPE) -> __OUTPUT, __TYPE, __OUTPUT>,
+                > = ::std::cell::RefCell::new(::souvenir::Memory::new(
+                    __souvenir_resolve as fn(&__TYPE) -> __OUTPUT,
+                ));
+            }
+
+            // The memory isn't borrowed while computing, so recursive calls can use it
+            let cached = MEMORY.with(|memory| {
+                memory.borrow().observe().get(&__ARG).cloned()
+            });
+            if let ::std::option::Option::Some(value) = cached {
+                return value;
+            }
+            let input = ::std::clone::Clone::clone(&__ARG);
+            let value = __souvenir_inner(__ARG);
+            MEMORY.with(|memory| {
+                ::std::clone::Clone::clone(memory.borrow_mut().resolve_or_insert(input, value))
+            })
+        }
+        ",
+        &[
+            ("__PREFIX", function.prefix.clone()),
+            ("__NAME", TokenTree::Ident(function.name.clone()).into()),
+            ("__ARG", TokenTree::Ident(argument.name).into()),
+            ("__PATTERN", argument.pattern),
+            ("__TYPE", argument.ty),
+            ("__OUTPUT", output.clone()),
+            ("__BODY", TokenTree::Group(function.body.clone()).into()),
+        ],
+    ))
+}
+
+/// Run a function without arguments only once per thread, with a thread-local `Recall`.
+///
+/// Later calls return a clone of the first result, so the return value must be `Clone`.
+///
+/// # Examples
+/// ```
+/// use souvenir::once;
+///
+/// #[once]
+/// fn greeting() -> String {
+///     println!("only printed once");
+///     String::from("hello")
+/// }
+///
+/// assert_eq!(greeting(), "hello");
+/// assert_eq!(greeting(), "hello");
+/// ```
+#[proc_macro_attribute]
+pub fn once(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return compile_error("`once` takes no arguments");
+    }
+    match ItemFn::parse(item).and_then(|function| expand_once(&function)) {
+        Ok(expanded) => expanded,
+        Err(message) => compile_error(message),
+    }
+}
+
+fn expand_once(function: &ItemFn) -> Result<TokenStream, &'static str> {
+    let Some(output) = &function.output else {
+        return Err("`once` needs a function returning a value");
+    };
+    if !function.inputs.stream().is_empty() {
+        return Err("`once` only supports functions without arguments");
+    }
+    Ok(expand(
+        "
+        __PREFIX fn __NAME() -> __OUTPUT {
+            fn __souvenir_inner() -> __OUTPUT __BODY
+
+            ::std::thread_local! {
+                static RECALL: ::std::cell::RefCell<
+                    ::souvenir::Recall<fn() -> __OUTPUT, __OUTPUT>,
+                > = ::std::cell::RefCell::new(::souvenir::Recall::new(
+                    __souvenir_inner as fn() -> __OUTPUT,
+                ));
+            }
+
+            RECALL.with(|recall| recall.borrow_mut().value())
+        }
+        ",
+        &[
+            ("__PREFIX", function.prefix.clone()),
+            ("__NAME", TokenTree::Ident(function.name.clone()).into()),
+            ("__OUTPUT", output.clone()),
+            ("__BODY", TokenTree::Group(function.body.clone()).into()),
+        ],
+    ))
+}
+
+/// Implement `Cached` for a resolver type with an inherent `compute` method.
+///
+/// The method must be `fn compute(&mut self, input: &TIn) -> TOut`,
+/// with the types given in a `#[cached(input = TIn, output = TOut)]` attribute.
+///
+/// # Examples
+/// ```
+/// use souvenir::Cached;
+///
+/// #[derive(Cached)]
+/// #[cached(input = String, output = usize)]
+/// struct WordCounter {
+///     separator: char,
+/// }
+///
+/// impl WordCounter {
+///     fn compute(&mut self, input: &String) -> usize {
+///         input.split(self.separator).count()
+///     }
+/// }
+///
+/// let mut counter = WordCounter { separator: ' ' }.cached();
+/// assert_eq!(counter.resolve(&"one two three".to_string()), 3);
+/// ```
+#[proc_macro_derive(Cached, attributes(cached))]
+pub fn derive_cached(item: TokenStream) -> TokenStream {
+    match expand_cached(item) {
+        Ok(expanded) => expanded,
+        Err(message) => compile_error(message),
+    }
+}
+
+fn expand_cached(item: TokenStream) -> Result<TokenStream, &'static str> {
+    let mut tokens = item.into_iter().peekable();
+    let mut types = None;
+    let mut name = None;
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(attribute) if attribute.delimiter() == Delimiter::Bracket => {
+                if let Some(found) = CachedTypes::parse(attribute.stream())? {
+                    types = Some(found);
+                }
+            }
+            TokenTree::Ident(keyword)
+                if matches!(keyword.to_string().as_str(), "struct" | "enum") =>
+            {
+                let Some(TokenTree::Ident(ident)) = tokens.next() else {
+                    return Err("expected a type name");
+                };
+                if matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<')
+                {
+                    return Err("generic types aren't supported");
+                }
+                name = Some(ident);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let name = name.ok_or("`Cached` can only be derived for a struct or an enum")?;
+    let types = types.ok_or("expected a `#[cached(input = ..., output = ...)]` attribute")?;
+
+    Ok(expand(
+        "
+        impl ::souvenir::Cached for __NAME {
+            type Input = __INPUT;
+            type Output = __OUTPUT;
+
+            fn cached(
+                mut self,
+            ) -> ::souvenir::Memory<
+                impl ::std::ops::FnMut(&Self::Input) -> Self::Output,
+                Self::Input,
+                Self::Output,
+            > {
+                ::souvenir::Memory::new(move |input: &__INPUT| self.compute(input))
+            }
+        }
+        ",
+        &[
+            ("__NAME", TokenTree::Ident(name).into()),
+            ("__INPUT", types.input),
+            ("__OUTPUT", types.output),
+        ],
+    ))
+}
+
+/// The types given by a `#[cached(input = ..., output = ...)]` attribute.
+struct CachedTypes {
+    input: TokenStream,
+    output: TokenStream,
+}
+
+impl CachedTypes {
+    /// Parse the inside of an attribute, or `None` if it's another attribute.
+    fn parse(attribute: TokenStream) -> Result<Option<Self>, &'static str> {
+        let mut tokens = attribute.into_iter();
+        let arguments = match (tokens.next(), tokens.next()) {
+            (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(arguments)))
+                if ident.to_string() == "cached" =>
+            {
+                arguments
+            }
+            _ => return Ok(None),
+        };
+
+        let (mut input, mut output) = (None, None);
+        for argument in split_arguments(arguments.stream()) {
+            let (key, ty) = match argument.as_slice() {
+                [TokenTree::Ident(key), TokenTree::Punct(equals), ty @ ..]
+                    if equals.as_char() == '=' && !ty.is_empty() =>
+                {
+                    (key.to_string(), ty.iter().cloned().collect())
+                }
+                _ => return Err("expected `input = ...` or `output = ...`"),
+            };
+            match key.as_str() {
+                "input" => input = Some(ty),
+                "output" => output = Some(ty),
+                _ => return Err("expected `input = ...` or `output = ...`"),
+            }
+        }
+
+        match (input, output) {
+            (Some(input), Some(output)) => Ok(Some(Self { input, output })),
+            _ => Err("`#[cached]` needs both an `input` and an `output` type"),
+        }
+    }
+}
+
+/// A free function, split into the parts the macros rearrange.
+struct ItemFn {
+    /// Attributes, visibility and qualifiers, everything before `fn`.
+    prefix: TokenStream,
+    name: Ident,
+    /// The parenthesized arguments.
+    inputs: Group,
+    /// The return type, without the arrow.
+    output: Option<TokenStream>,
+    body: Group,
+}
+
+impl ItemFn {
+    fn parse(item: TokenStream) -> Result<Self, &'static str> {
+        let mut tokens: Vec<_> = item.into_iter().collect();
+        let body = match tokens.pop() {
+            Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+            _ => return Err("expected a function with a body"),
+        };
+        let position = tokens
+            .iter()
+            .position(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn"))
+            .ok_or("expected a function")?;
+
+        let mut rest = tokens.split_off(position).into_iter().skip(1);
+        let Some(TokenTree::Ident(name)) = rest.next() else {
+            return Err("expected a function name");
+        };
+        let inputs = match rest.next() {
+            Some(TokenTree::Group(inputs)) if inputs.delimiter() == Delimiter::Parenthesis => {
+                inputs
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+                return Err("generic functions aren't supported");
+            }
+            _ => return Err("expected the function arguments"),
+        };
+
+        let rest: Vec<_> = rest.collect();
+        let output = match rest.as_slice() {
+            [] => None,
+            [TokenTree::Punct(dash), TokenTree::Punct(arrow), output @ ..]
+                if dash.as_char() == '-' && arrow.as_char() == '>' && !output.is_empty() =>
+            {
+                if has_where_clause(output) {
+                    return Err("`where` clauses aren't supported");
+                }
+                Some(output.iter().cloned().collect())
+            }
+            _ => return Err("expected a return type"),
+        };
+
+        Ok(Self {
+            prefix: tokens.into_iter().collect(),
+            name,
+            inputs,
+            output,
+            body,
+        })
+    }
+}
+
+fn has_where_clause(tokens: &[TokenTree]) -> bool {
+    tokens
+        .iter()
+        .any(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "where"))
+}
+
+/// The single argument of a memoized function.
+struct Argument {
+    /// The name the argument is bound to.
+    name: Ident,
+    /// The binding as written, possibly `mut`.
+    pattern: TokenStream,
+    ty: TokenStream,
+}
+
+impl Argument {
+    fn parse(inputs: TokenStream) -> Result<Self, &'static str> {
+        let mut arguments = split_arguments(inputs);
+        let (Some(argument), None) = (arguments.pop(), arguments.pop()) else {
+            return Err("`memoize` only supports functions taking exactly one argument");
+        };
+
+        let colon = argument
+            .iter()
+            .position(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':'))
+            .ok_or("expected a typed argument")?;
+        let (pattern, ty) = argument.split_at(colon);
+        let name = match pattern {
+            [TokenTree::Ident(name)] => name,
+            [TokenTree::Ident(keyword), TokenTree::Ident(name)] if keyword.to_string() == "mut" => {
+                name
+            }
+            _ => return Err("only plain argument names are supported, not patterns"),
+        };
+
+        Ok(Self {
+            name: name.clone(),
+            pattern: pattern.iter().cloned().collect(),
+            ty: ty[1..].iter().cloned().collect(),
+        })
+    }
+}
+
+/// Split arguments on their top-level commas, dropping a trailing one.
+fn split_arguments(inputs: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut arguments = vec![Vec::new()];
+    let mut depth = 0_usize;
+    let mut after_dash = false;
+    for token in inputs {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                ',' if depth == 0 => {
+                    arguments.push(Vec::new());
+                    after_dash = false;
+                    continue;
+                }
+                '<' => depth += 1,
+                // The arrow of a function pointer type isn't a closing bracket
+                '>' if !after_dash => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            after_dash = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+        } else {
+            after_dash = false;
+        }
+        if let Some(argument) = arguments.last_mut() {
+            argument.push(token);
+        }
+    }
+    arguments.retain(|argument| !argument.is_empty());
+    arguments
+}
+
+/// Parse the template, replacing the placeholder identifiers with their tokens.
+///
+/// The substituted tokens keep their spans, so errors in them point at the user's code.
+fn expand(template: &str, substitutions: &[(&str, TokenStream)]) -> TokenStream {
+    let template: TokenStream = template.parse().expect("templates are valid tokens");
+    substitute(template, substitutions)
+}
+
+fn substitute(tokens: TokenStream, substitutions: &[(&str, TokenStream)]) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(ident) => {
+                let name = ident.to_string();
+                substitutions
+                    .iter()
+                    .find(|(placeholder, _)| *placeholder == name)
+                    .map_or_else(
+                        || TokenTree::Ident(ident).into(),
+                        |(_, tokens)| tokens.clone(),
+                    )
+            }
+            TokenTree::Group(group) => {
+                let mut substituted =
+                    Group::new(group.delimiter(), substitute(group.stream(), substitutions));
+                substituted.set_span(group.span());
+                TokenTree::Group(substituted).into()
+            }
+            token => TokenStream::from(token),
+        })
+        .collect()
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("::core::compile_error!({message:?});")
+        .parse()
+        .expect("the error is valid tokens")
+}
@@ -0,0 +1,49 @@
+use souvenir::Cached;
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+#[derive(Cached)]
+#[cached(input = u32, output = u32)]
+struct Squarer {
+    calls: Rc<Cell<u32>>,
+}
+
+impl Squarer {
+    fn compute(&mut self, input: &u32) -> u32 {
+        self.calls.set(self.calls.get() + 1);
+        input * input
+    }
+}
+
+/// A resolver with generic input and output types in its attribute.
+#[derive(Cached)]
+#[cached(input = (String, u32), output = HashMap<String, u32>)]
+struct Tally;
+
+impl Tally {
+    #[allow(clippy::unused_self)]
+    fn compute(&mut self, input: &(String, u32)) -> HashMap<String, u32> {
+        HashMap::from([input.clone()])
+    }
+}
+
+#[test]
+fn it_only_computes_each_input_once() {
+    let calls = Rc::new(Cell::new(0));
+    let mut memory = Squarer {
+        calls: Rc::clone(&calls),
+    }
+    .cached();
+
+    assert_eq!(memory.resolve(&3), 9);
+    assert_eq!(memory.resolve(&3), 9);
+    assert_eq!(memory.resolve(&4), 16);
+
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn it_accepts_any_input_and_output_types() {
+    let mut memory = Tally.cached();
+    let input = ("unit".to_owned(), 1);
+    assert_eq!(memory.resolve(&input)["unit"], 1);
+}
@@ -0,0 +1,56 @@
+use souvenir::memoize;
+use std::cell::Cell;
+
+thread_local! {
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+fn calls() -> u32 {
+    CALLS.with(Cell::get)
+}
+
+#[memoize]
+fn shout(word: String) -> String {
+    CALLS.with(|calls| calls.set(calls.get() + 1));
+    word.to_uppercase() + "!"
+}
+
+#[memoize]
+fn fibonacci(n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        fibonacci(n - 1) + fibonacci(n - 2)
+    }
+}
+
+#[memoize]
+fn countdown(mut n: u32) -> Vec<u32> {
+    let mut steps = Vec::new();
+    while n > 0 {
+        steps.push(n);
+        n -= 1;
+    }
+    steps
+}
+
+#[test]
+fn it_only_calls_the_function_once_per_input() {
+    assert_eq!(shout("unit".to_owned()), "UNIT!");
+    assert_eq!(shout("unit".to_owned()), "UNIT!");
+    assert_eq!(calls(), 1);
+
+    assert_eq!(shout("test".to_owned()), "TEST!");
+    assert_eq!(calls(), 2);
+}
+
+#[test]
+fn it_memoizes_recursive_calls() {
+    assert_eq!(fibonacci(90), 2_880_067_194_370_816_120);
+}
+
+#[test]
+fn it_keeps_the_body_as_written() {
+    assert_eq!(countdown(3), [3, 2, 1]);
+    assert_eq!(countdown(0), []);
+}
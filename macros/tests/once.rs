@@ -0,0 +1,33 @@
+use souvenir::once;
+use std::{cell::Cell, thread};
+
+thread_local! {
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+#[once]
+fn answer() -> u32 {
+    CALLS.with(|calls| calls.set(calls.get() + 1));
+    42
+}
+
+#[test]
+fn it_runs_the_body_once() {
+    for _ in 0..10 {
+        assert_eq!(answer(), 42);
+    }
+    assert_eq!(CALLS.with(Cell::get), 1);
+}
+
+#[test]
+fn it_runs_the_body_once_per_thread() {
+    assert_eq!(answer(), 42);
+    let other = thread::spawn(|| {
+        answer();
+        answer();
+        CALLS.with(Cell::get)
+    });
+
+    assert_eq!(other.join().unwrap(), 1);
+    assert_eq!(CALLS.with(Cell::get), 1);
+}
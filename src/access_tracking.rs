@@ -0,0 +1,156 @@
+use crate::memory::Memory;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// A memory remembering when each input was last resolved, hit or miss.
+///
+/// Useful for auditing the cache, or for evicting entries nobody asks for anymore.
+pub struct AccessTrackingMemory<T, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    accessed: HashMap<TIn, Instant>,
+}
+
+impl<T, TIn, TOut> AccessTrackingMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    pub(crate) fn new(memory: Memory<T, TIn, TOut>) -> Self {
+        Self {
+            memory,
+            accessed: HashMap::new(),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// The access time of the input is updated either way.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_access_tracking();
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert!(doubler.last_access(&2).is_some());
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        let value = self.memory.resolve(input);
+        let now = Instant::now();
+        if let Some(accessed) = self.accessed.get_mut(input) {
+            *accessed = now;
+        } else {
+            self.accessed.insert(input.clone(), now);
+        }
+        value
+    }
+
+    /// When this input was last resolved, if it's cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_access_tracking();
+    /// assert_eq!(doubler.last_access(&2), None);
+    ///
+    /// doubler.resolve(&2);
+    /// assert!(doubler.last_access(&2).is_some());
+    /// ```
+    pub fn last_access(&self, input: &TIn) -> Option<Instant>
+    where
+        TIn: Eq + Hash,
+    {
+        self.accessed.get(input).copied()
+    }
+
+    /// How long since this input was last resolved, if it's cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// use std::time::Duration;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_access_tracking();
+    /// doubler.resolve(&2);
+    /// assert!(doubler.idle_for(&2).unwrap() < Duration::from_secs(1));
+    /// ```
+    pub fn idle_for(&self, input: &TIn) -> Option<Duration>
+    where
+        TIn: Eq + Hash,
+    {
+        self.last_access(input).map(|accessed| accessed.elapsed())
+    }
+
+    /// Remove every entry that hasn't been resolved for longer than `threshold`.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// use std::time::Duration;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_access_tracking();
+    /// doubler.resolve(&2);
+    ///
+    /// std::thread::sleep(Duration::from_millis(1));
+    /// doubler.evict_idle(Duration::ZERO);
+    /// assert_eq!(doubler.last_access(&2), None);
+    /// ```
+    pub fn evict_idle(&mut self, threshold: Duration)
+    where
+        TIn: Eq + Hash,
+    {
+        self.accessed
+            .retain(|_, accessed| accessed.elapsed() <= threshold);
+        let accessed = &self.accessed;
+        self.memory.gc(|input, _| accessed.contains_key(input));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn it_tracks_how_long_inputs_have_been_idle() {
+        let mut memory = Memory::new(|x: &u32| x * 2).with_access_tracking();
+        memory.resolve(&1);
+        memory.resolve(&2);
+
+        let first = memory.idle_for(&1).unwrap();
+        thread::sleep(Duration::from_millis(20));
+        let later = memory.idle_for(&1).unwrap();
+        assert!(later >= first + Duration::from_millis(20));
+
+        memory.resolve(&1); // A hit counts as an access too
+        assert!(memory.idle_for(&1).unwrap() < later);
+        assert!(memory.idle_for(&2).unwrap() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn it_evicts_idle_entries() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        })
+        .with_access_tracking();
+        memory.resolve(&1);
+        memory.resolve(&2);
+
+        thread::sleep(Duration::from_millis(30));
+        memory.resolve(&2);
+        memory.evict_idle(Duration::from_millis(20));
+
+        assert_eq!(memory.last_access(&1), None);
+        assert!(memory.last_access(&2).is_some());
+
+        memory.resolve(&1);
+        memory.resolve(&2);
+
+        assert_eq!(counter, 3);
+    }
+}
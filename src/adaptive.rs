@@ -0,0 +1,152 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A memory scanning a small list of entries, until it grows enough to need hashing.
+///
+/// Created with [`Memory::adaptive`](crate::Memory::adaptive).
+/// Comparing a handful of keys is cheaper than hashing one,
+/// so entries stay in a `Vec` until there are more than `linear_threshold`
+/// of them, and then move to a `HashMap` for good.
+pub struct AdaptiveMemory<T, TIn, TOut> {
+    remember: T,
+    linear_threshold: usize,
+    storage: Storage<TIn, TOut>,
+}
+
+enum Storage<TIn, TOut> {
+    Linear(Vec<(TIn, TOut)>),
+    Hashed(HashMap<TIn, TOut>),
+}
+
+impl<T, TIn, TOut> AdaptiveMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    pub(crate) const fn new(remember: T, linear_threshold: usize) -> Self {
+        Self {
+            remember,
+            linear_threshold,
+            storage: Storage::Linear(Vec::new()),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::adaptive(|x: &u32| x * 2, 8);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        let cached = match &self.storage {
+            Storage::Linear(entries) => entries
+                .iter()
+                .find(|(key, _)| key == input)
+                .map(|(_, value)| value),
+            Storage::Hashed(values) => values.get(input),
+        };
+        if let Some(value) = cached {
+            return value.clone();
+        }
+
+        let result = (self.remember)(input);
+        match &mut self.storage {
+            Storage::Linear(entries) => {
+                entries.push((input.clone(), result.clone()));
+                if entries.len() > self.linear_threshold {
+                    self.storage = Storage::Hashed(entries.drain(..).collect());
+                }
+            }
+            Storage::Hashed(values) => {
+                values.insert(input.clone(), result.clone());
+            }
+        }
+        result
+    }
+
+    /// How many entries are cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::adaptive(|x: &u32| x * 2, 2);
+    /// for x in 0..5 {
+    ///     doubler.resolve(&x);
+    /// }
+    /// assert_eq!(doubler.len(), 5);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Linear(entries) => entries.len(),
+            Storage::Hashed(values) => values.len(),
+        }
+    }
+
+    /// Check whether nothing is cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let doubler = Memory::adaptive(|x: &u32| x * 2, 8);
+    /// assert!(doubler.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_scans_while_small() {
+        let mut counter = 0;
+        let mut memory = AdaptiveMemory::new(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            3,
+        );
+        for x in [1, 2, 3, 1, 2, 3] {
+            assert_eq!(memory.resolve(&x), x * 2);
+        }
+
+        assert!(matches!(memory.storage, Storage::Linear(_)));
+        assert_eq!(memory.len(), 3);
+        drop(memory);
+        assert_eq!(counter, 3);
+    }
+
+    #[test]
+    fn it_switches_to_hashing_past_the_threshold() {
+        let mut counter = 0;
+        let mut memory = AdaptiveMemory::new(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            3,
+        );
+        for x in 0..4 {
+            memory.resolve(&x);
+        }
+        assert!(matches!(memory.storage, Storage::Hashed(_)));
+
+        // Nothing was lost in the move
+        for x in 0..4 {
+            assert_eq!(memory.resolve(&x), x * 2);
+        }
+        assert_eq!(memory.len(), 4);
+        drop(memory);
+        assert_eq!(counter, 4);
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    borrow::Borrow,
+    hash::Hash,
+    path::{Path, PathBuf},
+};
+
+/// A borrowed form of an input, that can be turned back into an owned one.
+///
+/// Used by [`Memory::resolve_key`](crate::Memory::resolve_key),
+/// so a `Memory` keyed by `String` can be resolved from a `&str` as well,
+/// only allocating the owned key on a miss.
+///
+/// # Examples
+/// ```
+/// use souvenir::AsKey;
+/// assert_eq!(AsKey::<String>::to_key("unit"), String::from("unit"));
+/// ```
+pub trait AsKey<TIn>: Eq + Hash
+where
+    TIn: Borrow<Self>,
+{
+    /// Build the owned input.
+    fn to_key(&self) -> TIn;
+}
+
+impl AsKey<String> for str {
+    fn to_key(&self) -> String {
+        self.to_owned()
+    }
+}
+
+impl AsKey<Self> for String {
+    fn to_key(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<T: Clone + Eq + Hash> AsKey<Vec<T>> for [T] {
+    fn to_key(&self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+impl<T: Clone + Eq + Hash> AsKey<Self> for Vec<T> {
+    fn to_key(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl AsKey<PathBuf> for Path {
+    fn to_key(&self) -> PathBuf {
+        self.to_path_buf()
+    }
+}
+
+impl AsKey<Self> for PathBuf {
+    fn to_key(&self) -> Self {
+        self.clone()
+    }
+}
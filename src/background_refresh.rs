@@ -0,0 +1,187 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A shared memory refreshing entries in the background once they get old.
+///
+/// An entry older than `refresh_after` is still served as is,
+/// while a fresh value is computed on another thread.
+/// The resolver is cloned into that thread, so it must be `Fn + Send + Sync + Clone`.
+pub struct BackgroundRefreshMemory<T, TIn, TOut> {
+    remember: T,
+    refresh_after: Duration,
+    entries: Arc<Mutex<HashMap<TIn, Entry<TOut>>>>,
+}
+
+struct Entry<TOut> {
+    value: TOut,
+    resolved_at: Instant,
+    refreshing: bool,
+}
+
+impl<TOut> Entry<TOut> {
+    fn new(value: TOut) -> Self {
+        Self {
+            value,
+            resolved_at: Instant::now(),
+            refreshing: false,
+        }
+    }
+}
+
+impl<T, TIn, TOut> BackgroundRefreshMemory<T, TIn, TOut>
+where
+    T: Fn(&TIn) -> TOut + Send + Sync + Clone + 'static,
+{
+    /// Create a new `BackgroundRefreshMemory`, refreshing entries older than `refresh_after`
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::BackgroundRefreshMemory;
+    /// use std::time::Duration;
+    /// let doubler = BackgroundRefreshMemory::new(|x: &u32| x * 2, Duration::from_secs(60));
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T, refresh_after: Duration) -> Self {
+        Self {
+            remember,
+            refresh_after,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the resolver runs on the calling thread.
+    /// On a stale hit, the old value is returned immediately
+    /// and the entry is recomputed on a background thread.
+    /// If that refresh panics, the old value is kept and the next stale hit tries again.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::BackgroundRefreshMemory;
+    /// use std::time::Duration;
+    /// let doubler = BackgroundRefreshMemory::new(|x: &u32| x * 2, Duration::from_secs(60));
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash + Send + 'static,
+        TOut: Clone + Send + 'static,
+    {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(entry) = entries.get_mut(input) {
+            let value = entry.value.clone();
+            if !entry.refreshing && entry.resolved_at.elapsed() >= self.refresh_after {
+                entry.refreshing = true;
+                drop(entries);
+                self.refresh(input.clone());
+            }
+            return value;
+        }
+        drop(entries);
+
+        let result = (self.remember)(input);
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(input.clone(), Entry::new(result.clone()));
+        result
+    }
+
+    fn refresh(&self, input: TIn)
+    where
+        TIn: Eq + Hash + Send + 'static,
+        TOut: Send + 'static,
+    {
+        let remember = self.remember.clone();
+        let entries = Arc::clone(&self.entries);
+        thread::spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| remember(&input)));
+            let mut entries = entries.lock().unwrap_or_else(PoisonError::into_inner);
+            match result {
+                Ok(value) => {
+                    entries.insert(input, Entry::new(value));
+                }
+                // Keep serving the old value, and retry on the next stale hit
+                Err(_) => {
+                    if let Some(entry) = entries.get_mut(&input) {
+                        entry.refreshing = false;
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    /// Wait for a background refresh to be observed, failing after a generous deadline.
+    fn eventually(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "condition never became true");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn it_serves_stale_values_while_refreshing() {
+        let generation = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&generation);
+        let memory = BackgroundRefreshMemory::new(
+            move |key: &u32| key * 10 + counter.fetch_add(1, Ordering::SeqCst),
+            Duration::from_millis(50),
+        );
+
+        assert_eq!(memory.resolve(&1), 10);
+        assert_eq!(memory.resolve(&1), 10); // Still fresh
+
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(memory.resolve(&1), 10); // Stale, refresh started
+
+        eventually(|| memory.resolve(&1) == 11);
+        assert_eq!(generation.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn it_retries_a_refresh_that_panicked() {
+        let failing = Arc::new(AtomicBool::new(false));
+        let generation = Arc::new(AtomicU32::new(0));
+        let (fail, counter) = (Arc::clone(&failing), Arc::clone(&generation));
+        let memory = BackgroundRefreshMemory::new(
+            move |key: &u32| {
+                assert!(!fail.load(Ordering::SeqCst), "flaky resolver");
+                key * 10 + counter.fetch_add(1, Ordering::SeqCst)
+            },
+            Duration::ZERO,
+        );
+        assert_eq!(memory.resolve(&1), 10);
+
+        failing.store(true, Ordering::SeqCst);
+        assert_eq!(memory.resolve(&1), 10); // Refresh started, and panics
+        eventually(|| !memory.entries.lock().unwrap()[&1].refreshing);
+
+        failing.store(false, Ordering::SeqCst);
+        eventually(|| memory.resolve(&1) != 10);
+    }
+
+    #[test]
+    fn it_resolves_misses_on_the_calling_thread() {
+        let memory = BackgroundRefreshMemory::new(
+            |_: &u32| thread::current().id(),
+            Duration::from_millis(50),
+        );
+
+        assert_eq!(memory.resolve(&1), thread::current().id());
+    }
+}
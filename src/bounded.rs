@@ -0,0 +1,203 @@
+use crate::memory::Memory;
+use std::{error::Error, fmt, hash::Hash};
+
+/// The error returned when a [`BoundedKeyMemory`] is full and the input is a miss.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityExceeded {
+    /// The most keys the memory may hold.
+    pub max: usize,
+}
+
+impl fmt::Display for CapacityExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory is full (max keys: {})", self.max)
+    }
+}
+
+impl Error for CapacityExceeded {}
+
+/// A memory that refuses to grow beyond a fixed number of keys.
+///
+/// Created with [`Memory::with_max_keys`](crate::Memory::with_max_keys).
+pub struct BoundedKeyMemory<T, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    max: usize,
+}
+
+impl<T, TIn, TOut> BoundedKeyMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    pub(crate) const fn new(memory: Memory<T, TIn, TOut>, max: usize) -> Self {
+        Self { memory, max }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// # Panics
+    /// Panics on a miss when the memory already holds `max` keys.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::with_max_keys(|x: &u32| x * 2, 1);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // A hit is always fine
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        match self.try_resolve(input) {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Search the memory for the previous result of this input,
+    /// without panicking when it's full.
+    ///
+    /// # Errors
+    /// Returns [`CapacityExceeded`] on a miss when the memory already holds `max` keys.
+    /// The resolver isn't called in that case.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{CapacityExceeded, Memory};
+    /// let mut doubler = Memory::with_max_keys(|x: &u32| x * 2, 1);
+    /// assert_eq!(doubler.try_resolve(&2), Ok(4));
+    /// assert_eq!(doubler.try_resolve(&3), Err(CapacityExceeded { max: 1 }));
+    /// ```
+    pub fn try_resolve(&mut self, input: &TIn) -> Result<TOut, CapacityExceeded>
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            return Ok(value.clone());
+        }
+        if self.len() >= self.max {
+            return Err(CapacityExceeded { max: self.max });
+        }
+
+        let result = self.memory.compute(input);
+        self.memory.store(input.clone(), result.clone());
+        Ok(result)
+    }
+
+    /// How many keys the memory currently holds.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::with_max_keys(|x: &u32| x * 2, 10);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.memory.values.len()
+    }
+
+    /// The fraction of the key limit in use, from `0.0` (empty) to `1.0` (full).
+    ///
+    /// A memory with a limit of zero keys is always full.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::with_max_keys(|x: &u32| x * 2, 4);
+    /// doubler.resolve(&1);
+    /// assert_eq!(doubler.capacity_ratio(), 0.25);
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn capacity_ratio(&self) -> f64 {
+        if self.max == 0 {
+            return 1.0;
+        }
+        self.len() as f64 / self.max as f64
+    }
+
+    /// Check whether the memory holds no keys yet.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let doubler = Memory::with_max_keys(|x: &u32| x * 2, 10);
+    /// assert!(doubler.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.memory.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_resolves_within_the_limit() {
+        let mut counter = 0;
+        let mut memory = Memory::with_max_keys(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            3,
+        );
+
+        assert_eq!(memory.resolve(&1), 2);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&3), 6);
+        assert_eq!(memory.resolve(&1), 2);
+        assert_eq!(memory.len(), 3);
+
+        assert_eq!(counter, 3);
+    }
+
+    #[test]
+    fn it_errors_when_the_limit_is_exceeded() {
+        let mut counter = 0;
+        let mut memory = Memory::with_max_keys(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            2,
+        );
+
+        assert_eq!(memory.try_resolve(&1), Ok(2));
+        assert_eq!(memory.try_resolve(&2), Ok(4));
+        assert_eq!(memory.try_resolve(&3), Err(CapacityExceeded { max: 2 }));
+        assert_eq!(memory.try_resolve(&2), Ok(4));
+        assert_eq!(memory.len(), 2);
+
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "max keys: 1")]
+    fn it_panics_when_the_limit_is_exceeded() {
+        let mut memory = Memory::with_max_keys(|x: &u32| x * 2, 1);
+        memory.resolve(&1);
+        memory.resolve(&2);
+    }
+
+    #[test]
+    fn it_reports_the_capacity_ratio() {
+        let mut memory = Memory::with_max_keys(|x: &u32| x * 2, 8);
+        assert!(memory.capacity_ratio().abs() < f64::EPSILON);
+
+        for x in 0..6 {
+            memory.resolve(&x);
+        }
+        assert!((memory.capacity_ratio() - 0.75).abs() < f64::EPSILON);
+
+        memory.resolve(&0); // A hit doesn't use more capacity
+        assert!((memory.capacity_ratio() - 0.75).abs() < f64::EPSILON);
+    }
+}
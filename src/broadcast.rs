@@ -0,0 +1,166 @@
+use crate::recall::Recall;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// A thread-safe one-time lazy evaluation, whose value is shared with receivers.
+///
+/// Created by [`Recall::broadcast`].
+pub struct BroadcastRecall<T, TOut> {
+    evaluator: Mutex<T>,
+    value: Arc<OnceLock<TOut>>,
+}
+
+/// A lightweight handle to the value of a [`BroadcastRecall`].
+///
+/// Receivers never evaluate anything themselves,
+/// they only see the value once the master has computed it.
+pub struct BroadcastReceiver<TOut> {
+    value: Arc<OnceLock<TOut>>,
+}
+
+impl<T, TOut> BroadcastRecall<T, TOut>
+where
+    T: FnMut() -> TOut,
+{
+    pub(crate) fn new(recall: Recall<T, TOut>) -> Self {
+        let value = OnceLock::new();
+        if let Some(evaluated) = recall.value {
+            let _ = value.set(evaluated);
+        }
+
+        Self {
+            evaluator: Mutex::new(recall.evaluator),
+            value: Arc::new(value),
+        }
+    }
+
+    /// Resolve the lazy-evaluated function and return the value.
+    ///
+    /// If the function has already resolved, the value will return immediately.
+    /// Concurrent callers block until the single evaluation completes.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let (master, _) = Recall::new(|| 42).broadcast();
+    /// assert_eq!(*master.value(), 42);
+    /// ```
+    pub fn value(&self) -> &TOut {
+        self.value.get_or_init(|| {
+            let mut evaluator = self
+                .evaluator
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            evaluator()
+        })
+    }
+
+    /// Create another receiver for the value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let (master, _) = Recall::new(|| 42).broadcast();
+    /// let receiver = master.subscribe();
+    /// master.value();
+    /// assert_eq!(receiver.get(), Some(&42));
+    /// ```
+    pub fn subscribe(&self) -> BroadcastReceiver<TOut> {
+        BroadcastReceiver {
+            value: Arc::clone(&self.value),
+        }
+    }
+}
+
+impl<TOut> BroadcastReceiver<TOut> {
+    /// The value, if the master has computed it already.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let (master, receiver) = Recall::new(|| 42).broadcast();
+    /// assert_eq!(receiver.get(), None);
+    ///
+    /// master.value();
+    /// assert_eq!(receiver.get(), Some(&42));
+    /// ```
+    #[must_use]
+    pub fn get(&self) -> Option<&TOut> {
+        self.value.get()
+    }
+
+    /// Block until the master has computed the value, then return it.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let (master, receiver) = Recall::new(|| 42).broadcast();
+    /// std::thread::spawn(move || {
+    ///     master.value();
+    /// });
+    /// assert_eq!(*receiver.wait(), 42);
+    /// ```
+    #[must_use]
+    pub fn wait(&self) -> &TOut {
+        self.value.wait()
+    }
+}
+
+impl<TOut> Clone for BroadcastReceiver<TOut> {
+    fn clone(&self) -> Self {
+        Self {
+            value: Arc::clone(&self.value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        thread,
+    };
+
+    #[test]
+    fn it_shares_the_value_with_every_receiver() {
+        let counter = AtomicU32::new(0);
+        let (master, receiver) = Recall::new(|| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            String::from("certificate")
+        })
+        .broadcast();
+
+        thread::scope(|scope| {
+            let receivers: Vec<_> = (0..4)
+                .map(|_| {
+                    let receiver = receiver.clone();
+                    scope.spawn(move || receiver.wait().clone())
+                })
+                .collect();
+            let masters: Vec<_> = (0..4).map(|_| scope.spawn(|| master.value())).collect();
+
+            for handle in receivers {
+                assert_eq!(handle.join().unwrap(), "certificate");
+            }
+            for handle in masters {
+                assert!(std::ptr::eq(handle.join().unwrap(), receiver.wait()));
+            }
+        });
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn it_keeps_an_already_evaluated_value() {
+        let mut counter = 0;
+        let mut recall = Recall::new(|| {
+            counter += 1;
+            42
+        });
+        recall.value();
+
+        let (master, receiver) = recall.broadcast();
+        assert_eq!(receiver.get(), Some(&42));
+        assert_eq!(*master.value(), 42);
+        assert_eq!(counter, 1);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::memory::Memory;
+
+/// A resolver type that can be wrapped in a [`Memory`] of its own.
+///
+/// With the `proc-macros` feature, `#[derive(Cached)]` implements it
+/// from an inherent `fn compute(&mut self, input: &TIn) -> TOut` method,
+/// with the types given as `#[cached(input = TIn, output = TOut)]`.
+///
+/// # Examples
+/// ```
+/// use souvenir::{Cached, Memory};
+///
+/// struct Multiplier(u32);
+///
+/// impl Cached for Multiplier {
+///     type Input = u32;
+///     type Output = u32;
+///
+///     fn cached(self) -> Memory<impl FnMut(&u32) -> u32, u32, u32> {
+///         Memory::new(move |x: &u32| x * self.0)
+///     }
+/// }
+///
+/// let mut tripler = Multiplier(3).cached();
+/// assert_eq!(tripler.resolve(&2), 6);
+/// ```
+pub trait Cached: Sized {
+    /// The input the resolver is called with.
+    type Input;
+    /// The value the resolver produces.
+    type Output;
+
+    /// Build a memory resolving its misses with this resolver.
+    #[allow(clippy::type_complexity)] // `impl Trait` can't be named by an alias
+    fn cached(self) -> Memory<impl FnMut(&Self::Input) -> Self::Output, Self::Input, Self::Output>;
+}
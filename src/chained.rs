@@ -0,0 +1,83 @@
+use crate::recall::Recall;
+
+/// A [`Recall`] whose evaluator is built from the value of another one.
+///
+/// Created with [`Recall::chain`].
+pub struct ChainedRecall<T, F, G, TOut, U> {
+    first: Recall<T, TOut>,
+    chain: F,
+    second: Option<Recall<G, U>>,
+}
+
+impl<T, F, G, TOut, U> ChainedRecall<T, F, G, TOut, U>
+where
+    T: FnMut() -> TOut,
+    F: FnMut(TOut) -> Recall<G, U>,
+    G: FnMut() -> U,
+    TOut: Clone,
+    U: Clone,
+{
+    pub(crate) const fn new(first: Recall<T, TOut>, chain: F) -> Self {
+        Self {
+            first,
+            chain,
+            second: None,
+        }
+    }
+
+    /// Resolve both steps of the chain and return the value of the second.
+    ///
+    /// Each step is only evaluated once.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut url = Recall::new(|| "localhost:8080").chain(|host| Recall::new(move || format!("http://{host}/")));
+    /// assert_eq!(url.value(), "http://localhost:8080/");
+    /// ```
+    pub fn value(&mut self) -> U {
+        let first = &mut self.first;
+        let chain = &mut self.chain;
+        self.second
+            .get_or_insert_with(|| chain(first.value()))
+            .value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Recall;
+
+    #[test]
+    fn it_evaluates_each_step_once() {
+        let mut loads = 0;
+        let mut connects = 0;
+        let mut connection = Recall::new(|| {
+            loads += 1;
+            String::from("db.internal")
+        })
+        .chain(|host| {
+            connects += 1;
+            Recall::new(move || format!("connected to {host}"))
+        });
+
+        assert_eq!(connection.value(), "connected to db.internal");
+        assert_eq!(connection.value(), "connected to db.internal");
+
+        assert_eq!(loads, 1);
+        assert_eq!(connects, 1);
+    }
+
+    #[test]
+    fn it_evaluates_nothing_until_read() {
+        let mut loads = 0;
+        let connection = Recall::new(|| {
+            loads += 1;
+            2
+        })
+        .chain(|x| Recall::new(move || x * 2));
+
+        assert!(connection.second.is_none());
+        assert!(!connection.first.is_evaluated());
+    }
+}
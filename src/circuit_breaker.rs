@@ -0,0 +1,267 @@
+use crate::fallible_memory::FallibleMemory;
+use std::{
+    error::Error,
+    fmt,
+    hash::Hash,
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
+
+/// The error returned by a [`CircuitBreakerMemory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitBreakerError<E> {
+    /// The resolver failed too many times in a row, and wasn't called.
+    CircuitOpen,
+    /// The resolver was called and failed.
+    Failed(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CircuitBreakerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CircuitOpen => write!(f, "circuit open, the resolver wasn't called"),
+            Self::Failed(error) => write!(f, "resolver failed: {error}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for CircuitBreakerError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CircuitOpen => None,
+            Self::Failed(error) => Some(error),
+        }
+    }
+}
+
+/// Whether a [`CircuitBreakerMemory`] lets misses through to its resolver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Misses call the resolver.
+    Closed,
+    /// Misses fail without calling the resolver.
+    Open,
+    /// The next miss calls the resolver, to probe whether it recovered.
+    HalfOpen,
+}
+
+/// A fallible memory that stops calling its resolver after too many failures in a row.
+///
+/// Created with [`FallibleMemory::with_circuit_breaker`](crate::FallibleMemory::with_circuit_breaker).
+/// Hits are always served, whatever the state of the circuit.
+pub struct CircuitBreakerMemory<T, TIn, TOut, E> {
+    memory: FallibleMemory<T, TIn, TOut, E>,
+    threshold: NonZeroUsize,
+    reset_after: Duration,
+    failures: usize,
+    opened_at: Option<Instant>,
+    now: fn() -> Instant,
+}
+
+impl<T, TIn, TOut, E> CircuitBreakerMemory<T, TIn, TOut, E>
+where
+    T: FnMut(&TIn) -> Result<TOut, E>,
+{
+    pub(crate) fn new(
+        memory: FallibleMemory<T, TIn, TOut, E>,
+        threshold: NonZeroUsize,
+        reset_after: Duration,
+    ) -> Self {
+        Self::with_clock(memory, threshold, reset_after, Instant::now)
+    }
+
+    pub(crate) fn with_clock(
+        memory: FallibleMemory<T, TIn, TOut, E>,
+        threshold: NonZeroUsize,
+        reset_after: Duration,
+        now: fn() -> Instant,
+    ) -> Self {
+        Self {
+            memory,
+            threshold,
+            reset_after,
+            failures: 0,
+            opened_at: None,
+            now,
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// A success closes the circuit again,
+    /// and a failure while half-open opens it for another `reset_after`.
+    ///
+    /// # Errors
+    /// Returns [`CircuitBreakerError::CircuitOpen`] on a miss while the circuit is open,
+    /// without calling the resolver.
+    /// Returns [`CircuitBreakerError::Failed`] when the resolver fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{CircuitBreakerError, FallibleMemory};
+    /// use std::{num::NonZeroUsize, time::Duration};
+    /// let mut parser = FallibleMemory::new(|x: &&str| x.parse::<u32>())
+    ///     .with_circuit_breaker(NonZeroUsize::new(1).unwrap(), Duration::from_secs(30));
+    /// assert!(matches!(parser.try_resolve(&"x"), Err(CircuitBreakerError::Failed(_))));
+    /// assert_eq!(parser.try_resolve(&"42"), Err(CircuitBreakerError::CircuitOpen));
+    /// ```
+    pub fn try_resolve(&mut self, input: &TIn) -> Result<TOut, CircuitBreakerError<E>>
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.cached(input) {
+            return Ok(value);
+        }
+        if self.state() == CircuitState::Open {
+            return Err(CircuitBreakerError::CircuitOpen);
+        }
+
+        match self.memory.try_resolve(input) {
+            Ok(value) => {
+                self.failures = 0;
+                self.opened_at = None;
+                Ok(value)
+            }
+            Err(error) => {
+                self.failures += 1;
+                if self.opened_at.is_some() || self.failures >= self.threshold.get() {
+                    self.opened_at = Some((self.now)());
+                }
+                Err(CircuitBreakerError::Failed(error))
+            }
+        }
+    }
+
+    /// Whether misses currently reach the resolver.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{CircuitState, FallibleMemory};
+    /// use std::{num::NonZeroUsize, time::Duration};
+    /// let parser = FallibleMemory::new(|x: &&str| x.parse::<u32>())
+    ///     .with_circuit_breaker(NonZeroUsize::new(3).unwrap(), Duration::from_secs(30));
+    /// assert_eq!(parser.state(), CircuitState::Closed);
+    /// ```
+    #[must_use]
+    pub fn state(&self) -> CircuitState {
+        let Some(opened_at) = self.opened_at else {
+            return CircuitState::Closed;
+        };
+        if (self.now)().duration_since(opened_at) < self.reset_after {
+            CircuitState::Open
+        } else {
+            CircuitState::HalfOpen
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_clock::{advance, mock_now};
+    use std::cell::Cell;
+
+    #[test]
+    fn it_goes_through_the_whole_cycle() {
+        let healthy = Cell::new(false);
+        let calls = Cell::new(0);
+        let mut memory = CircuitBreakerMemory::with_clock(
+            FallibleMemory::new(|x: &u32| {
+                calls.set(calls.get() + 1);
+                if healthy.get() {
+                    Ok(x * 2)
+                } else {
+                    Err("down")
+                }
+            }),
+            NonZeroUsize::new(2).unwrap(),
+            Duration::from_secs(10),
+            mock_now,
+        );
+
+        assert_eq!(
+            memory.try_resolve(&1),
+            Err(CircuitBreakerError::Failed("down"))
+        );
+        assert_eq!(memory.state(), CircuitState::Closed);
+        assert_eq!(
+            memory.try_resolve(&1),
+            Err(CircuitBreakerError::Failed("down"))
+        );
+        assert_eq!(memory.state(), CircuitState::Open);
+
+        assert_eq!(
+            memory.try_resolve(&1),
+            Err(CircuitBreakerError::CircuitOpen)
+        );
+        assert_eq!(calls.get(), 2);
+
+        advance(Duration::from_secs(10));
+        assert_eq!(memory.state(), CircuitState::HalfOpen);
+        healthy.set(true);
+        assert_eq!(memory.try_resolve(&1), Ok(2));
+        assert_eq!(memory.state(), CircuitState::Closed);
+
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn it_reopens_when_the_probe_fails() {
+        let mut memory = CircuitBreakerMemory::with_clock(
+            FallibleMemory::new(|_: &u32| Err::<u32, _>("down")),
+            NonZeroUsize::new(3).unwrap(),
+            Duration::from_secs(10),
+            mock_now,
+        );
+        for _ in 0..3 {
+            memory.try_resolve(&1).unwrap_err();
+        }
+        assert_eq!(memory.state(), CircuitState::Open);
+
+        advance(Duration::from_secs(10));
+        assert_eq!(
+            memory.try_resolve(&1),
+            Err(CircuitBreakerError::Failed("down"))
+        );
+        assert_eq!(memory.state(), CircuitState::Open);
+        assert_eq!(
+            memory.try_resolve(&1),
+            Err(CircuitBreakerError::CircuitOpen)
+        );
+    }
+
+    #[test]
+    fn it_serves_hits_while_open() {
+        let mut memory = CircuitBreakerMemory::with_clock(
+            FallibleMemory::new(|x: &u32| if *x == 0 { Err("zero") } else { Ok(x * 2) }),
+            NonZeroUsize::new(1).unwrap(),
+            Duration::from_secs(10),
+            mock_now,
+        );
+        assert_eq!(memory.try_resolve(&1), Ok(2));
+        memory.try_resolve(&0).unwrap_err();
+
+        assert_eq!(memory.state(), CircuitState::Open);
+        assert_eq!(memory.try_resolve(&1), Ok(2));
+        assert_eq!(
+            memory.try_resolve(&2),
+            Err(CircuitBreakerError::CircuitOpen)
+        );
+    }
+
+    #[test]
+    fn it_resets_the_count_on_success() {
+        let mut memory = CircuitBreakerMemory::with_clock(
+            FallibleMemory::new(|x: &u32| if *x == 0 { Err("zero") } else { Ok(x * 2) }),
+            NonZeroUsize::new(2).unwrap(),
+            Duration::from_secs(10),
+            mock_now,
+        );
+        memory.try_resolve(&0).unwrap_err();
+        memory.try_resolve(&1).unwrap();
+        memory.try_resolve(&0).unwrap_err();
+
+        assert_eq!(memory.state(), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex, PoisonError},
+    thread::{self, JoinHandle},
+};
+
+/// A memory warmed up ahead of time on a background thread.
+///
+/// Created with [`Memory::cold_start`](crate::Memory::cold_start).
+/// Because the resolver runs on another thread, it must be `Fn + Send + Sync`.
+pub struct ColdStartMemory<T, TIn, TOut> {
+    remember: Arc<T>,
+    values: Arc<Mutex<HashMap<TIn, TOut>>>,
+    warming: Option<JoinHandle<()>>,
+}
+
+impl<T, TIn, TOut> ColdStartMemory<T, TIn, TOut>
+where
+    T: Fn(&TIn) -> TOut + Send + Sync + 'static,
+    TIn: Eq + Hash + Send + 'static,
+    TOut: Send + 'static,
+{
+    pub(crate) fn new(remember: T, keys: Vec<TIn>) -> Self {
+        let remember = Arc::new(remember);
+        let values = Arc::new(Mutex::new(HashMap::new()));
+
+        let warming = {
+            let remember = Arc::clone(&remember);
+            let values = Arc::clone(&values);
+            thread::spawn(move || {
+                for key in keys {
+                    let result = remember(&key);
+                    values
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert(key, result);
+                }
+            })
+        };
+
+        Self {
+            remember,
+            values,
+            warming: Some(warming),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss while the background thread is still warming up,
+    /// waits for it to finish before looking again.
+    ///
+    /// # Panics
+    /// Resumes the panic of a resolver that panicked on the background thread.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::cold_start(|x: &u32| x * 2, vec![1, 2, 3]);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&5), 10);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone,
+        TOut: Clone,
+    {
+        if let Some(value) = self.cached(input) {
+            return value;
+        }
+        if self.warming.is_some() {
+            self.wait();
+            if let Some(value) = self.cached(input) {
+                return value;
+            }
+        }
+
+        let result = (self.remember)(input);
+        self.values
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(input.clone(), result.clone());
+        result
+    }
+
+    /// Check whether the background thread has finished warming up.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::cold_start(|x: &u32| x * 2, vec![1, 2, 3]);
+    /// doubler.wait();
+    /// assert!(doubler.is_warm());
+    /// ```
+    #[must_use]
+    pub fn is_warm(&self) -> bool {
+        self.warming.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Block until the background thread has finished warming up.
+    ///
+    /// # Panics
+    /// Resumes the panic of a resolver that panicked on the background thread.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::cold_start(|x: &u32| x * 2, vec![1, 2, 3]);
+    /// doubler.wait();
+    /// assert_eq!(doubler.resolve(&3), 6);
+    /// ```
+    pub fn wait(&mut self) {
+        if let Some(handle) = self.warming.take() {
+            if let Err(panic) = handle.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+    }
+
+    fn cached(&self, input: &TIn) -> Option<TOut>
+    where
+        TOut: Clone,
+    {
+        self.values
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(input)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Memory;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn it_does_not_resolve_warmed_keys_again() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let mut memory = Memory::cold_start(
+            move |key: &u32| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                key * 2
+            },
+            vec![1, 2, 3],
+        );
+
+        memory.wait();
+        assert!(memory.is_warm());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        assert_eq!(memory.resolve(&1), 2);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&3), 6);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn it_waits_for_warming_before_resolving_a_miss() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let mut memory = Memory::cold_start(
+            move |key: &u32| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                thread::sleep(std::time::Duration::from_millis(10));
+                key * 2
+            },
+            vec![1, 2],
+        );
+
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&4), 8);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+}
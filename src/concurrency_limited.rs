@@ -0,0 +1,155 @@
+use crate::sync::SyncMemory;
+use std::{
+    hash::Hash,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+/// A shared memory bounding how many resolver calls run at the same time.
+///
+/// Threads missing while the limit is reached wait for a running call to finish.
+/// Hits never wait.
+pub struct ConcurrencyLimitedMemory<T, TIn, TOut> {
+    memory: SyncMemory<T, TIn, TOut>,
+    permits: Semaphore,
+}
+
+/// A counting semaphore, handing out a fixed number of permits.
+struct Semaphore {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+/// A permit taken from a [`Semaphore`], given back on drop.
+struct Permit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Semaphore {
+    const fn new(permits: usize) -> Self {
+        Self {
+            available: Mutex::new(permits),
+            released: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        self.available
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn acquire(&self) -> Permit<'_> {
+        let mut available = self.lock();
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *available -= 1;
+        drop(available);
+        Permit { semaphore: self }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        *self.semaphore.lock() += 1;
+        self.semaphore.released.notify_one();
+    }
+}
+
+impl<T, TIn, TOut> ConcurrencyLimitedMemory<T, TIn, TOut>
+where
+    T: Fn(&TIn) -> TOut,
+{
+    /// Wrap a shared memory, running at most `max_concurrent` resolver calls at once
+    ///
+    /// # Panics
+    /// Panics if `max_concurrent` is zero, as no resolver call could ever run.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{ConcurrencyLimitedMemory, SyncMemory};
+    /// let doubler = ConcurrencyLimitedMemory::new(SyncMemory::new(|x: &u32| x * 2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(memory: SyncMemory<T, TIn, TOut>, max_concurrent: usize) -> Self {
+        assert!(max_concurrent > 0, "max_concurrent must be at least 1");
+        Self {
+            memory,
+            permits: Semaphore::new(max_concurrent),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, waits for a free slot before calling the resolver.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SyncMemory;
+    /// let doubler = SyncMemory::new(|x: &u32| x * 2).with_concurrency_limit(1);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.cached(input) {
+            return value;
+        }
+
+        let result = {
+            let _permit = self.permits.acquire();
+            (self.memory.remember)(input)
+        };
+        self.memory.store(input.clone(), result.clone());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    #[test]
+    fn it_never_runs_more_resolvers_than_the_limit() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let memory = SyncMemory::new(|key: &usize| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+            key * 2
+        })
+        .with_concurrency_limit(2);
+
+        thread::scope(|scope| {
+            for key in 0..8 {
+                let memory = &memory;
+                scope.spawn(move || assert_eq!(memory.resolve(&key), key * 2));
+            }
+        });
+
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak <= 2, "{peak} resolvers ran at once");
+    }
+
+    #[test]
+    fn it_does_not_wait_on_a_hit() {
+        let memory = SyncMemory::new(|key: &u32| key * 2).with_concurrency_limit(1);
+        memory.resolve(&1);
+
+        let _held = memory.permits.acquire();
+        assert_eq!(memory.resolve(&1), 2);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::memory::Memory;
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    io::{Stderr, Write},
+};
+
+/// A memory reporting every hit and miss, to help debugging.
+///
+/// Reports are written to stderr by default, and only in debug builds.
+/// In release builds, the memory behaves exactly like the wrapped one.
+pub struct DebugMemory<T, TIn, TOut, W = Stderr> {
+    memory: Memory<T, TIn, TOut>,
+    sink: W,
+}
+
+impl<T, TIn, TOut, W> DebugMemory<T, TIn, TOut, W>
+where
+    T: FnMut(&TIn) -> TOut,
+    TIn: Debug,
+    TOut: Debug,
+    W: Write,
+{
+    pub(crate) const fn new(memory: Memory<T, TIn, TOut>, sink: W) -> Self {
+        Self { memory, sink }
+    }
+
+    /// Search the memory for the previous result of this input, reporting the outcome.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).debug();
+    /// assert_eq!(doubler.resolve(&2), 4); // [SOUVENIR] miss key=2
+    /// assert_eq!(doubler.resolve(&2), 4); // [SOUVENIR] hit key=2 value=4
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            #[cfg(debug_assertions)]
+            let _ = writeln!(self.sink, "[SOUVENIR] hit key={input:?} value={value:?}");
+            value.clone()
+        } else {
+            #[cfg(debug_assertions)]
+            let _ = writeln!(self.sink, "[SOUVENIR] miss key={input:?}");
+            let result = self.memory.compute(input);
+            self.memory.store(input.clone(), result.clone());
+            result
+        }
+    }
+
+    /// The sink reports are written to.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).debug_to(Vec::new());
+    /// doubler.resolve(&2);
+    /// assert!(!doubler.sink().is_empty());
+    /// ```
+    pub const fn sink(&self) -> &W {
+        &self.sink
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn it_reports_hits_and_misses() {
+        let mut memory = Memory::new(|key: &u32| key * 2).debug_to(Vec::new());
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&3), 6);
+
+        let output = String::from_utf8(memory.sink().clone()).unwrap();
+        assert_eq!(
+            output,
+            "[SOUVENIR] miss key=2\n[SOUVENIR] hit key=2 value=4\n[SOUVENIR] miss key=3\n"
+        );
+    }
+
+    #[test]
+    fn it_keeps_caching_semantics() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        })
+        .debug_to(io::sink());
+
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(counter, 1);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::recall::Recall;
+
+/// A [`Recall`] with a default value, readable before it has been evaluated.
+///
+/// Reading only needs a shared borrow, so the value can be handed out
+/// by reference whether or not the evaluation has happened yet.
+pub struct DefaultedRecall<T, TOut> {
+    recall: Recall<T, TOut>,
+    default: TOut,
+}
+
+impl<T, TOut> DefaultedRecall<T, TOut>
+where
+    T: FnMut() -> TOut,
+{
+    pub(crate) const fn new(recall: Recall<T, TOut>, default: TOut) -> Self {
+        Self { recall, default }
+    }
+
+    /// The evaluated value, or the default if the evaluation hasn't happened yet.
+    ///
+    /// Never triggers the evaluation.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let answer = Recall::new(|| 42).with_default(0);
+    /// assert_eq!(*answer.value(), 0);
+    /// ```
+    pub const fn value(&self) -> &TOut {
+        match &self.recall.value {
+            Some(value) => value,
+            None => &self.default,
+        }
+    }
+
+    /// Resolve the lazy-evaluated function and return the value.
+    ///
+    /// If the function has already resolved, the value will return immediately.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut answer = Recall::new(|| 42).with_default(0);
+    /// assert_eq!(*answer.evaluate(), 42);
+    /// assert_eq!(*answer.value(), 42);
+    /// ```
+    pub fn evaluate(&mut self) -> &TOut {
+        let recall = &mut self.recall;
+        recall.value.get_or_insert_with(&mut recall.evaluator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Recall;
+
+    #[test]
+    fn it_returns_the_default_before_evaluation() {
+        let mut counter = 0;
+        let recall = Recall::new(|| {
+            counter += 1;
+            42
+        })
+        .with_default(0);
+
+        assert_eq!(*recall.value(), 0);
+        assert_eq!(*recall.value(), 0);
+        assert_eq!(counter, 0);
+    }
+
+    #[test]
+    fn it_returns_the_value_after_evaluation() {
+        let mut counter = 0;
+        let mut recall = Recall::new(|| {
+            counter += 1;
+            42
+        })
+        .with_default(0);
+
+        assert_eq!(*recall.evaluate(), 42);
+        assert_eq!(*recall.evaluate(), 42);
+        assert_eq!(*recall.value(), 42);
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_shares_the_value_by_reference() {
+        let recall = Recall::new(|| String::from("unit")).with_default(String::from("test"));
+        let first = recall.value();
+        let second = recall.value();
+        assert!(std::ptr::eq(first, second));
+    }
+}
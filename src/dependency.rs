@@ -0,0 +1,315 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+};
+
+/// A memory that tracks which entries depend on which.
+/// Invalidating an entry also invalidates everything depending on it.
+pub struct DependencyMemory<T, TIn, TOut> {
+    remember: T,
+    values: HashMap<TIn, TOut>,
+    dependents: HashMap<TIn, Vec<TIn>>,
+}
+
+impl<T, TIn, TOut> DependencyMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Create a new `DependencyMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::DependencyMemory;
+    /// let mut doubler = DependencyMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self {
+            remember,
+            values: HashMap::new(),
+            dependents: HashMap::new(),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result of the resolver is stored with the input value as the key.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::DependencyMemory;
+    /// let mut doubler = DependencyMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.values.get(input) {
+            value.clone()
+        } else {
+            let result = (self.remember)(input);
+            self.values.insert(input.clone(), result.clone());
+            result
+        }
+    }
+
+    /// Resolve the input, recording that it depends on the given inputs.
+    ///
+    /// When any of `depends_on` is invalidated, this input is invalidated too.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::DependencyMemory;
+    /// let mut memory = DependencyMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(memory.resolve(&1), 2);
+    /// assert_eq!(memory.resolve_dependent(&2, &[1]), 4);
+    /// ```
+    pub fn resolve_dependent(&mut self, input: &TIn, depends_on: &[TIn]) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        for dependency in depends_on {
+            let dependents = self.dependents.entry(dependency.clone()).or_default();
+            if !dependents.contains(input) {
+                dependents.push(input.clone());
+            }
+        }
+        self.resolve(input)
+    }
+
+    /// Remove the input from the memory, along with every entry depending on it.
+    ///
+    /// Dependencies are followed recursively, so an entire chain is cleared at once.
+    /// The dependencies of every removed entry are forgotten along with it.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::DependencyMemory;
+    /// let mut memory = DependencyMemory::new(|x: &u32| x * 2);
+    /// memory.resolve(&1);
+    /// memory.resolve_dependent(&2, &[1]);
+    ///
+    /// memory.invalidate(&1);
+    /// assert!(!memory.contains(&2));
+    /// ```
+    pub fn invalidate(&mut self, input: &TIn)
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        let mut visited = HashSet::new();
+        let mut pending = vec![input.clone()];
+        while let Some(key) = pending.pop() {
+            if let Some(dependents) = self.dependents.remove(&key) {
+                pending.extend(
+                    dependents
+                        .into_iter()
+                        .filter(|dependent| !visited.contains(dependent)),
+                );
+            }
+            self.values.remove(&key);
+            visited.insert(key);
+        }
+
+        // A removed entry has to declare its dependencies again once re-resolved
+        self.dependents.retain(|_, dependents| {
+            dependents.retain(|dependent| !visited.contains(dependent));
+            !dependents.is_empty()
+        });
+    }
+
+    /// Check whether the memory holds a value for this input
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::DependencyMemory;
+    /// let mut memory = DependencyMemory::new(|x: &u32| x * 2);
+    /// assert!(!memory.contains(&1));
+    /// memory.resolve(&1);
+    /// assert!(memory.contains(&1));
+    /// ```
+    pub fn contains(&self, input: &TIn) -> bool
+    where
+        TIn: Eq + Hash,
+    {
+        self.values.contains_key(input)
+    }
+
+    /// Render the cached entries and their dependencies as a Graphviz DOT graph.
+    ///
+    /// Each cached input is a node, with an edge to every input it depends on.
+    /// Lines are sorted, so the output is stable for the same graph.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::DependencyMemory;
+    /// let mut memory = DependencyMemory::new(|x: &u32| x * 2);
+    /// memory.resolve(&1);
+    /// memory.resolve_dependent(&2, &[1]);
+    ///
+    /// assert_eq!(
+    ///     memory.to_dot(),
+    ///     "digraph {\n    \"1\";\n    \"2\";\n    \"2\" -> \"1\";\n}\n"
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String
+    where
+        TIn: Display + Eq + Hash,
+    {
+        let node = |input: &TIn| {
+            // DOT strings only escape quotes and backslashes
+            let mut id = String::from('"');
+            for c in input.to_string().chars() {
+                if matches!(c, '"' | '\\') {
+                    id.push('\\');
+                }
+                id.push(c);
+            }
+            id.push('"');
+            id
+        };
+        let mut nodes: Vec<_> = self.values.keys().map(node).collect();
+        let mut edges: Vec<_> = self
+            .dependents
+            .iter()
+            .filter(|(dependency, _)| self.contains(dependency))
+            .flat_map(|(dependency, dependents)| {
+                dependents
+                    .iter()
+                    .filter(|dependent| self.contains(dependent))
+                    .map(move |dependent| format!("{} -> {}", node(dependent), node(dependency)))
+            })
+            .collect();
+        nodes.sort_unstable();
+        edges.sort_unstable();
+
+        let mut dot = String::from("digraph {\n");
+        for line in nodes.iter().chain(&edges) {
+            dot.push_str("    ");
+            dot.push_str(line);
+            dot.push_str(";\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_invalidates_dependents_recursively() {
+        let mut counter = 0;
+        let tool = |key: &u32| {
+            counter += 1;
+            key * 2
+        };
+
+        let mut memory = DependencyMemory::new(tool);
+        memory.resolve(&1);
+        memory.resolve_dependent(&2, &[1]);
+        memory.resolve_dependent(&3, &[2]);
+        memory.resolve(&4);
+
+        memory.invalidate(&1);
+        assert!(!memory.contains(&1));
+        assert!(!memory.contains(&2));
+        assert!(!memory.contains(&3));
+        assert!(memory.contains(&4));
+
+        assert_eq!(memory.resolve_dependent(&3, &[2]), 6);
+        drop(memory);
+        assert_eq!(counter, 5);
+    }
+
+    #[test]
+    fn it_only_invalidates_downstream() {
+        let mut memory = DependencyMemory::new(|key: &u32| key * 2);
+        memory.resolve(&1);
+        memory.resolve_dependent(&2, &[1]);
+        memory.resolve_dependent(&3, &[2]);
+
+        memory.invalidate(&2);
+        assert!(memory.contains(&1));
+        assert!(!memory.contains(&2));
+        assert!(!memory.contains(&3));
+    }
+
+    #[test]
+    fn it_survives_dependency_cycles() {
+        let mut memory = DependencyMemory::new(|key: &u32| key * 2);
+        memory.resolve_dependent(&1, &[2]);
+        memory.resolve_dependent(&2, &[1]);
+
+        memory.invalidate(&1);
+        assert!(!memory.contains(&1));
+        assert!(!memory.contains(&2));
+    }
+
+    #[test]
+    fn it_forgets_dependencies_of_invalidated_entries() {
+        let mut memory = DependencyMemory::new(|key: &u32| key * 2);
+        memory.resolve(&1);
+        memory.resolve_dependent(&2, &[1]);
+        memory.resolve_dependent(&3, &[2, 4]);
+
+        memory.invalidate(&1);
+        assert!(memory.dependents.is_empty());
+
+        // Re-resolved without declaring the dependency again
+        memory.resolve(&2);
+        memory.invalidate(&1);
+        assert!(memory.contains(&2));
+    }
+
+    #[test]
+    fn it_renders_the_dependency_graph() {
+        let mut memory = DependencyMemory::new(|key: &&str| key.len());
+        memory.resolve(&"base");
+        memory.resolve_dependent(&"left", &["base"]);
+        memory.resolve_dependent(&"right", &["base"]);
+        memory.resolve_dependent(&"top", &["left", "right"]);
+
+        let dot = memory.to_dot();
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.ends_with("}\n"));
+        for node in ["base", "left", "right", "top"] {
+            assert!(dot.contains(&format!("    \"{node}\";\n")), "{dot}");
+        }
+        for edge in [
+            "\"left\" -> \"base\"",
+            "\"right\" -> \"base\"",
+            "\"top\" -> \"left\"",
+            "\"top\" -> \"right\"",
+        ] {
+            assert!(dot.contains(edge), "{dot}");
+        }
+        assert_eq!(dot.matches("->").count(), 4);
+    }
+
+    #[test]
+    fn it_escapes_node_ids_for_dot() {
+        let mut memory = DependencyMemory::new(|key: &&str| key.len());
+        memory.resolve(&"say \"hi\"\\n");
+        memory.resolve(&"it's\u{1b}");
+
+        let dot = memory.to_dot();
+        assert!(dot.contains("    \"say \\\"hi\\\"\\\\n\";\n"), "{dot}");
+        assert!(dot.contains("    \"it's\u{1b}\";\n"), "{dot}");
+    }
+
+    #[test]
+    fn it_leaves_invalidated_entries_out_of_the_graph() {
+        let mut memory = DependencyMemory::new(|key: &u32| key * 2);
+        memory.resolve(&1);
+        memory.resolve_dependent(&2, &[1]);
+        memory.resolve(&3);
+
+        memory.invalidate(&1);
+        assert_eq!(memory.to_dot(), "digraph {\n    \"3\";\n}\n");
+    }
+}
@@ -0,0 +1,242 @@
+use crate::memory::Memory;
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+/// A view into a single entry of a [`Memory`](crate::Memory), looked up by a borrowed input.
+///
+/// Returned by [`Memory::entry_ref`](crate::Memory::entry_ref).
+pub enum EntryRef<'a, TIn, TOut, Q: ?Sized> {
+    /// The input has a cached value.
+    Occupied(OccupiedEntryRef<'a, TOut>),
+    /// The input has no cached value yet.
+    Vacant(VacantEntryRef<'a, TIn, TOut, Q>),
+}
+
+/// An entry with a cached value.
+pub struct OccupiedEntryRef<'a, TOut> {
+    value: &'a mut TOut,
+}
+
+/// An entry without a cached value.
+///
+/// The owned input is only built when a value is inserted.
+pub struct VacantEntryRef<'a, TIn, TOut, Q: ?Sized> {
+    values: &'a mut HashMap<TIn, TOut>,
+    #[cfg(feature = "ordering")]
+    order: &'a mut Vec<TIn>,
+    input: &'a Q,
+}
+
+impl<'a, TIn, TOut, Q> EntryRef<'a, TIn, TOut, Q>
+where
+    TIn: Borrow<Q> + Eq + Hash,
+    Q: Eq + Hash + ?Sized,
+{
+    pub(crate) fn new<T>(memory: &'a mut Memory<T, TIn, TOut>, input: &'a Q) -> Self {
+        let values = &mut memory.values;
+        if values.contains_key(input) {
+            Self::Occupied(OccupiedEntryRef {
+                value: values.get_mut(input).expect("entry was just checked"),
+            })
+        } else {
+            Self::Vacant(VacantEntryRef {
+                values,
+                #[cfg(feature = "ordering")]
+                order: &mut memory.order,
+                input,
+            })
+        }
+    }
+
+    /// Get the cached value, inserting the given one if the entry is vacant.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// assert_eq!(*lengths.entry_ref("unit").or_insert(10), 10);
+    /// assert_eq!(*lengths.entry_ref("unit").or_insert(20), 10);
+    /// ```
+    pub fn or_insert(self, value: TOut) -> &'a mut TOut
+    where
+        TIn: for<'b> From<&'b Q> + Clone,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(value),
+        }
+    }
+
+    /// Get the cached value, inserting the result of `f` if the entry is vacant.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// assert_eq!(*lengths.entry_ref("unit").or_insert_with(|| 10), 10);
+    /// ```
+    pub fn or_insert_with<F>(self, f: F) -> &'a mut TOut
+    where
+        TIn: for<'b> From<&'b Q> + Clone,
+        F: FnOnce() -> TOut,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(f()),
+        }
+    }
+}
+
+impl<'a, TOut> OccupiedEntryRef<'a, TOut> {
+    /// Get the cached value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{EntryRef, Memory};
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// lengths.resolve_ref_borrow("unit");
+    ///
+    /// if let EntryRef::Occupied(entry) = lengths.entry_ref("unit") {
+    ///     assert_eq!(*entry.get(), 4);
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn get(&self) -> &TOut {
+        self.value
+    }
+
+    /// Get mutable access to the cached value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{EntryRef, Memory};
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// lengths.resolve_ref_borrow("unit");
+    ///
+    /// if let EntryRef::Occupied(mut entry) = lengths.entry_ref("unit") {
+    ///     *entry.get_mut() += 1;
+    /// }
+    /// assert_eq!(*lengths.resolve_ref_borrow("unit"), 5);
+    /// ```
+    pub const fn get_mut(&mut self) -> &mut TOut {
+        self.value
+    }
+
+    /// Convert the entry into mutable access to the cached value, bound to the memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{EntryRef, Memory};
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// lengths.resolve_ref_borrow("unit");
+    ///
+    /// if let EntryRef::Occupied(entry) = lengths.entry_ref("unit") {
+    ///     *entry.into_mut() = 0;
+    /// }
+    /// assert_eq!(*lengths.resolve_ref_borrow("unit"), 0);
+    /// ```
+    #[must_use]
+    pub const fn into_mut(self) -> &'a mut TOut {
+        self.value
+    }
+}
+
+impl<'a, TIn, TOut, Q> VacantEntryRef<'a, TIn, TOut, Q>
+where
+    TIn: Borrow<Q> + Eq + Hash,
+    Q: Eq + Hash + ?Sized,
+{
+    /// The borrowed input used for the lookup.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{EntryRef, Memory};
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    ///
+    /// if let EntryRef::Vacant(entry) = lengths.entry_ref("unit") {
+    ///     assert_eq!(entry.key(), "unit");
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn key(&self) -> &Q {
+        self.input
+    }
+
+    /// Insert a value for the input, building the owned input from the borrowed one.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{EntryRef, Memory};
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    ///
+    /// if let EntryRef::Vacant(entry) = lengths.entry_ref("unit") {
+    ///     entry.insert(10);
+    /// }
+    /// assert_eq!(*lengths.resolve_ref_borrow("unit"), 10);
+    /// ```
+    pub fn insert(self, value: TOut) -> &'a mut TOut
+    where
+        TIn: for<'b> From<&'b Q> + Clone,
+    {
+        let input = TIn::from(self.input);
+        #[cfg(feature = "ordering")]
+        self.order.push(input.clone());
+        self.values.entry(input).or_insert(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        static CONVERSIONS: Cell<u32> = const { Cell::new(0) };
+    }
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct Key(String);
+
+    impl Borrow<str> for Key {
+        fn borrow(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl From<&str> for Key {
+        fn from(value: &str) -> Self {
+            CONVERSIONS.with(|count| count.set(count.get() + 1));
+            Self(value.to_owned())
+        }
+    }
+
+    #[test]
+    fn it_only_builds_the_key_on_insert() {
+        let mut memory = Memory::new(|key: &Key| key.0.len());
+
+        assert!(matches!(memory.entry_ref("unit"), EntryRef::Vacant(_)));
+        assert_eq!(CONVERSIONS.with(Cell::get), 0);
+
+        assert_eq!(*memory.entry_ref("unit").or_insert(4), 4);
+        assert_eq!(CONVERSIONS.with(Cell::get), 1);
+
+        assert_eq!(*memory.entry_ref("unit").or_insert(10), 4);
+        assert!(matches!(memory.entry_ref("unit"), EntryRef::Occupied(_)));
+        assert_eq!(CONVERSIONS.with(Cell::get), 1);
+    }
+
+    #[test]
+    fn it_works_with_string_keys() {
+        let mut memory = Memory::new(|key: &String| key.len());
+        memory.resolve(&String::from("unit"));
+
+        match memory.entry_ref("unit") {
+            EntryRef::Occupied(entry) => assert_eq!(*entry.get(), 4),
+            EntryRef::Vacant(_) => panic!("expected a cached value"),
+        }
+        match memory.entry_ref("test") {
+            EntryRef::Occupied(_) => panic!("expected no cached value"),
+            EntryRef::Vacant(entry) => assert_eq!(*entry.insert(7), 7),
+        }
+        assert_eq!(memory.resolve(&String::from("test")), 7);
+    }
+}
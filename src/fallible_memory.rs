@@ -0,0 +1,113 @@
+use crate::circuit_breaker::CircuitBreakerMemory;
+use std::{
+    collections::HashMap, hash::Hash, marker::PhantomData, num::NonZeroUsize, time::Duration,
+};
+
+/// A memory whose resolver may fail.
+///
+/// Only successful results are remembered,
+/// a failed input is resolved again on the next call.
+pub struct FallibleMemory<T, TIn, TOut, E> {
+    remember: T,
+    values: HashMap<TIn, TOut>,
+    _error: PhantomData<E>,
+}
+
+impl<T, TIn, TOut, E> FallibleMemory<T, TIn, TOut, E>
+where
+    T: FnMut(&TIn) -> Result<TOut, E>,
+{
+    /// Create a new `FallibleMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleMemory;
+    /// let mut parser = FallibleMemory::new(|x: &String| x.parse::<u32>());
+    /// assert_eq!(parser.try_resolve(&"42".to_string()), Ok(42));
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self {
+            remember,
+            values: HashMap::new(),
+            _error: PhantomData,
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// # Errors
+    /// Returns the error of the resolver when it fails.
+    /// Nothing is remembered in that case.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleMemory;
+    /// let mut parser = FallibleMemory::new(|x: &String| x.parse::<u32>());
+    /// assert!(parser.try_resolve(&"not a number".to_string()).is_err());
+    /// ```
+    pub fn try_resolve(&mut self, input: &TIn) -> Result<TOut, E>
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.cached(input) {
+            return Ok(value);
+        }
+        let result = (self.remember)(input)?;
+        self.values.insert(input.clone(), result.clone());
+        Ok(result)
+    }
+
+    /// Stop calling the resolver once it fails `threshold` times in a row.
+    ///
+    /// After `reset_after`, a single call is let through to probe the resolver again.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleMemory;
+    /// use std::{num::NonZeroUsize, time::Duration};
+    /// let mut parser = FallibleMemory::new(|x: &String| x.parse::<u32>())
+    ///     .with_circuit_breaker(NonZeroUsize::new(3).unwrap(), Duration::from_secs(30));
+    /// assert_eq!(parser.try_resolve(&"42".to_string()), Ok(42));
+    /// ```
+    pub fn with_circuit_breaker(
+        self,
+        threshold: NonZeroUsize,
+        reset_after: Duration,
+    ) -> CircuitBreakerMemory<T, TIn, TOut, E> {
+        CircuitBreakerMemory::new(self, threshold, reset_after)
+    }
+
+    pub(crate) fn cached(&self, input: &TIn) -> Option<TOut>
+    where
+        TIn: Eq + Hash,
+        TOut: Clone,
+    {
+        self.values.get(input).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_only_remembers_successes() {
+        let mut counter = 0;
+        let mut memory = FallibleMemory::new(|x: &i32| {
+            counter += 1;
+            if *x < 0 {
+                Err("negative")
+            } else {
+                Ok(x * 2)
+            }
+        });
+
+        assert_eq!(memory.try_resolve(&2), Ok(4));
+        assert_eq!(memory.try_resolve(&2), Ok(4));
+        assert_eq!(memory.try_resolve(&-1), Err("negative"));
+        assert_eq!(memory.try_resolve(&-1), Err("negative"));
+
+        assert_eq!(counter, 3);
+    }
+}
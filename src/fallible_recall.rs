@@ -0,0 +1,143 @@
+use crate::retry::RetryRecall;
+use std::{marker::PhantomData, time::Duration};
+
+/// A one-time lazy evaluation that may fail.
+///
+/// Only a successful result is remembered,
+/// a failed evaluation is attempted again on the next call.
+pub struct FallibleRecall<T, TOut, E> {
+    pub(crate) evaluator: T,
+    pub(crate) value: Option<TOut>,
+    pub(crate) _error: PhantomData<E>,
+}
+
+impl<T, TOut, E> FallibleRecall<T, TOut, E>
+where
+    T: FnMut() -> Result<TOut, E>,
+    TOut: Clone,
+{
+    /// Create a new `FallibleRecall`, providing the lazy-evaluated function
+    /// that will resolve the expected value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleRecall;
+    /// let mut parsed = FallibleRecall::new(|| "42".parse::<u32>());
+    /// assert_eq!(parsed.try_value(), Ok(42));
+    /// ```
+    pub const fn new(evaluator: T) -> Self {
+        Self {
+            evaluator,
+            value: None,
+            _error: PhantomData,
+        }
+    }
+
+    /// Resolve the lazy-evaluated function and return the value.
+    ///
+    /// If the function has already succeeded, the value will return immediately.
+    ///
+    /// # Errors
+    /// Returns the error of the evaluator when it fails.
+    /// Nothing is remembered in that case.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleRecall;
+    /// let mut parsed = FallibleRecall::new(|| "not a number".parse::<u32>());
+    /// assert!(parsed.try_value().is_err());
+    /// ```
+    pub fn try_value(&mut self) -> Result<TOut, E> {
+        if let Some(value) = &self.value {
+            Ok(value.clone())
+        } else {
+            let value = (self.evaluator)()?;
+            self.value = Some(value.clone());
+            Ok(value)
+        }
+    }
+
+    /// Retry a failing evaluation, up to `max_attempts` times per call.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleRecall;
+    /// let mut attempts = 0;
+    /// let mut flaky = FallibleRecall::new(|| {
+    ///     attempts += 1;
+    ///     if attempts < 3 { Err("not yet") } else { Ok(attempts) }
+    /// })
+    /// .retry(3);
+    ///
+    /// assert_eq!(flaky.try_value(), Ok(3));
+    /// ```
+    pub fn retry(self, max_attempts: usize) -> RetryRecall<T, TOut, E, fn(usize) -> Duration> {
+        RetryRecall::new(self, max_attempts, |_| Duration::ZERO)
+    }
+
+    /// Retry a failing evaluation, up to `max_attempts` times per call,
+    /// sleeping between attempts.
+    ///
+    /// The backoff receives the number of failed attempts so far
+    /// and returns how long to wait before the next one.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleRecall;
+    /// use std::time::Duration;
+    /// let mut attempts = 0;
+    /// let mut flaky = FallibleRecall::new(|| {
+    ///     attempts += 1;
+    ///     if attempts < 2 { Err("not yet") } else { Ok(attempts) }
+    /// })
+    /// .retry_with_backoff(3, |failures| Duration::from_millis(failures as u64));
+    ///
+    /// assert_eq!(flaky.try_value(), Ok(2));
+    /// ```
+    pub const fn retry_with_backoff<B>(
+        self,
+        max_attempts: usize,
+        backoff: B,
+    ) -> RetryRecall<T, TOut, E, B>
+    where
+        B: Fn(usize) -> Duration,
+    {
+        RetryRecall::new(self, max_attempts, backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_remembers_success() {
+        let mut counter = 0;
+        let mut recall = FallibleRecall::new(|| -> Result<u32, ()> {
+            counter += 1;
+            Ok(42)
+        });
+
+        assert_eq!(recall.try_value(), Ok(42));
+        assert_eq!(recall.try_value(), Ok(42));
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_does_not_remember_failure() {
+        let mut counter = 0;
+        let mut recall = FallibleRecall::new(|| {
+            counter += 1;
+            if counter == 1 {
+                Err("failed")
+            } else {
+                Ok(42)
+            }
+        });
+
+        assert_eq!(recall.try_value(), Err("failed"));
+        assert_eq!(recall.try_value(), Ok(42));
+        assert_eq!(recall.try_value(), Ok(42));
+        assert_eq!(counter, 2);
+    }
+}
@@ -0,0 +1,92 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A read-only memory, which can no longer resolve or store anything.
+///
+/// Created by freezing a pre-warmed [`Memory`](crate::Memory), to hand out
+/// to code that should only read from it.
+/// Mutation is rejected at compile time:
+///
+/// ```compile_fail
+/// use souvenir::Memory;
+/// let mut frozen = Memory::new(|x: &u32| x * 2).freeze();
+/// frozen.resolve(&2);
+/// ```
+pub struct FrozenMemory<TIn, TOut> {
+    values: HashMap<TIn, TOut>,
+}
+
+impl<TIn, TOut> FrozenMemory<TIn, TOut> {
+    pub(crate) const fn new(values: HashMap<TIn, TOut>) -> Self {
+        Self { values }
+    }
+
+    /// Look up the cached value of this input.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let frozen = doubler.freeze();
+    /// assert_eq!(frozen.get(&2), Some(&4));
+    /// assert_eq!(frozen.get(&3), None);
+    /// ```
+    pub fn get(&self, input: &TIn) -> Option<&TOut>
+    where
+        TIn: Eq + Hash,
+    {
+        self.values.get(input)
+    }
+
+    /// Iterate over every cached input and its value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let frozen = doubler.freeze();
+    /// assert_eq!(frozen.iter().collect::<Vec<_>>(), [(&2, &4)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&TIn, &TOut)> {
+        self.values.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Memory;
+    use std::thread;
+
+    #[test]
+    fn it_keeps_the_warmed_entries() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        for key in 0..5 {
+            memory.resolve(&key);
+        }
+
+        let frozen = memory.freeze();
+        assert_eq!(frozen.iter().count(), 5);
+        assert!((0..5).all(|key| frozen.get(&key) == Some(&(key * 2))));
+        assert_eq!(frozen.get(&5), None);
+    }
+
+    #[test]
+    fn it_can_be_shared_across_threads() {
+        // The resolver is dropped, so a non-`Sync` resolver doesn't matter
+        let counter = std::cell::Cell::new(0);
+        let mut memory = Memory::new(|key: &u32| {
+            counter.set(counter.get() + 1);
+            key * 2
+        });
+        memory.resolve(&2);
+
+        let frozen = memory.freeze();
+        thread::scope(|scope| {
+            let reader = scope.spawn(|| frozen.get(&2).copied());
+            assert_eq!(reader.join().unwrap(), Some(4));
+        });
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A memory invalidated in bulk through a generation counter.
+///
+/// Every entry remembers the generation it was computed in.
+/// Bumping the generation makes all existing entries stale at once,
+/// regardless of how many there are.
+pub struct GenerationalMemory<T, TIn, TOut> {
+    remember: T,
+    generation: u64,
+    values: HashMap<TIn, (TOut, u64)>,
+}
+
+impl<T, TIn, TOut> GenerationalMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Create a new `GenerationalMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::GenerationalMemory;
+    /// let mut doubler = GenerationalMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.generation(), 0);
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self {
+            remember,
+            generation: 0,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Search the memory for a result of this input from the current generation
+    ///
+    /// Entries from an older generation are recomputed and replaced.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::GenerationalMemory;
+    /// let mut doubler = GenerationalMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        match self.values.get(input) {
+            Some((value, generation)) if *generation == self.generation => value.clone(),
+            _ => {
+                let result = (self.remember)(input);
+                self.values
+                    .insert(input.clone(), (result.clone(), self.generation));
+                result
+            }
+        }
+    }
+
+    /// Start a new generation, making every existing entry stale.
+    ///
+    /// Stale entries are recomputed lazily, on their next `resolve`.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::GenerationalMemory;
+    /// let mut doubler = GenerationalMemory::new(|x: &u32| x * 2);
+    /// doubler.bump();
+    /// assert_eq!(doubler.generation(), 1);
+    /// ```
+    pub const fn bump(&mut self) {
+        self.generation += 1;
+    }
+
+    /// The current generation of the memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::GenerationalMemory;
+    /// let doubler = GenerationalMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.generation(), 0);
+    /// ```
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_recomputes_after_a_bump() {
+        let mut counter = 0;
+        let tool = |key: &u32| {
+            counter += 1;
+            key * 2
+        };
+
+        let mut memory = GenerationalMemory::new(tool);
+        for key in 0..3 {
+            assert_eq!(memory.resolve(&key), key * 2);
+            assert_eq!(memory.resolve(&key), key * 2);
+        }
+
+        memory.bump();
+        for key in 0..3 {
+            assert_eq!(memory.resolve(&key), key * 2);
+            assert_eq!(memory.resolve(&key), key * 2);
+        }
+        drop(memory);
+
+        assert_eq!(counter, 6);
+    }
+
+    #[test]
+    fn it_serves_the_latest_generation() {
+        let mut generation = 0;
+        let mut memory = GenerationalMemory::new(|key: &u32| {
+            generation += 1;
+            key + generation
+        });
+
+        assert_eq!(memory.resolve(&10), 11);
+        memory.bump();
+        memory.bump();
+        assert_eq!(memory.resolve(&10), 12);
+        assert_eq!(memory.resolve(&10), 12);
+    }
+}
@@ -0,0 +1,96 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+/// A memory identifying inputs by a custom hash, rather than their own `Hash` and `Eq`.
+///
+/// Created with [`Memory::with_key_hasher`](crate::Memory::with_key_hasher).
+/// Inputs the hasher maps to the same `u64` share one entry,
+/// which is the point when the hasher skips fields like timestamps,
+/// but also means an unlucky collision returns another input's value.
+/// Only use a hasher whose collisions you can live with.
+pub struct HashedKeyMemory<T, H, TIn, TOut> {
+    remember: T,
+    hasher: H,
+    values: HashMap<u64, TOut>,
+    _input: PhantomData<fn(&TIn)>,
+}
+
+impl<T, H, TIn, TOut> HashedKeyMemory<T, H, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+    H: Fn(&TIn) -> u64,
+{
+    pub(crate) const fn new(remember: T, hasher: H, values: HashMap<u64, TOut>) -> Self {
+        Self {
+            remember,
+            hasher,
+            values,
+            _input: PhantomData,
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result is stored under the hash of the input.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut lengths = Memory::new(|x: &String| x.len())
+    ///     .with_key_hasher(|x: &String| x.to_lowercase().len() as u64);
+    /// assert_eq!(lengths.resolve(&String::from("unit")), 4);
+    /// assert_eq!(lengths.resolve(&String::from("test")), 4); // Same hash, same entry
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TOut: Clone,
+    {
+        let key = (self.hasher)(input);
+        if let Some(value) = self.values.get(&key) {
+            return value.clone();
+        }
+        let result = (self.remember)(input);
+        self.values.insert(key, result.clone());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Memory;
+    use std::hash::{BuildHasher, RandomState};
+
+    #[derive(PartialEq, Eq, Hash)]
+    struct Request {
+        path: String,
+        timestamp: u64,
+    }
+
+    #[test]
+    fn it_shares_entries_between_inputs_with_the_same_hash() {
+        let state = RandomState::new();
+        let mut counter = 0;
+        let mut memory = Memory::new(|request: &Request| {
+            counter += 1;
+            request.path.len()
+        })
+        .with_key_hasher(|request: &Request| state.hash_one(&request.path));
+
+        let first = Request {
+            path: String::from("/unit"),
+            timestamp: 1,
+        };
+        let second = Request {
+            path: String::from("/unit"),
+            timestamp: 2,
+        };
+        let other = Request {
+            path: String::from("/test/other"),
+            timestamp: 1,
+        };
+        assert_eq!(memory.resolve(&first), 5);
+        assert_eq!(memory.resolve(&second), 5);
+        assert_eq!(memory.resolve(&other), 11);
+
+        assert_eq!(counter, 2);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::memory::Memory;
+use std::hash::Hash;
+
+/// A memory asking a hook for permission before every resolver call.
+///
+/// Useful for circuit breakers, rate limits or audit logs,
+/// anything that needs to know a resolver is about to run.
+/// Hits don't involve the hook.
+pub struct HookedMemory<T, H, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    hook: H,
+}
+
+impl<T, H, TIn, TOut> HookedMemory<T, H, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+    H: FnMut(&TIn) -> bool,
+{
+    pub(crate) const fn new(memory: Memory<T, TIn, TOut>, hook: H) -> Self {
+        Self { memory, hook }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the resolver only runs if the hook returns `true`.
+    /// Returns `None` when the hook cancelled the call, without caching anything.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_pre_resolve_hook(|x| *x < 10);
+    /// assert_eq!(doubler.resolve(&2), Some(4));
+    /// assert_eq!(doubler.resolve(&20), None);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> Option<TOut>
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            return Some(value.clone());
+        }
+        if !(self.hook)(input) {
+            return None;
+        }
+
+        let result = self.memory.compute(input);
+        self.memory.store(input.clone(), result.clone());
+        Some(result)
+    }
+
+    /// Search the memory for the previous result of this input,
+    /// returning `fallback` if the hook cancelled the resolver call.
+    ///
+    /// The fallback isn't cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_pre_resolve_hook(|x| *x < 10);
+    /// assert_eq!(doubler.resolve_or(&20, 0), 0);
+    /// ```
+    pub fn resolve_or(&mut self, input: &TIn, fallback: TOut) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        self.resolve(input).unwrap_or(fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_lets_the_hook_cancel_the_resolver() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        })
+        .with_pre_resolve_hook(|_| false);
+
+        assert_eq!(memory.resolve(&2), None);
+        assert_eq!(memory.resolve_or(&2, 7), 7);
+
+        assert_eq!(counter, 0);
+    }
+
+    #[test]
+    fn it_resolves_normally_when_the_hook_allows_it() {
+        let mut asked = Vec::new();
+        let mut memory = Memory::new(|x: &u32| x * 2).with_pre_resolve_hook(|x| {
+            asked.push(*x);
+            true
+        });
+
+        assert_eq!(memory.resolve(&2), Some(4));
+        assert_eq!(memory.resolve(&2), Some(4));
+        assert_eq!(memory.resolve(&3), Some(6));
+
+        assert_eq!(asked, [2, 3]);
+    }
+}
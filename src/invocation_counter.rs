@@ -0,0 +1,104 @@
+use crate::memory::Memory;
+use std::{collections::HashMap, hash::Hash};
+
+/// A layer over [`Memory`] counting how often each input is requested.
+/// Caching behaves exactly like the wrapped memory,
+/// hits and misses are both counted.
+pub struct InvocationCounter<T, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    counts: HashMap<TIn, u64>,
+}
+
+impl<T, TIn, TOut> InvocationCounter<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Wrap a memory, starting every count at zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{InvocationCounter, Memory};
+    /// let mut doubler = InvocationCounter::new(Memory::new(|x: &u32| x * 2));
+    /// assert_eq!(doubler.invocation_count(&2), 0);
+    /// ```
+    pub fn new(memory: Memory<T, TIn, TOut>) -> Self {
+        Self {
+            memory,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Resolve the input through the wrapped memory, counting the request.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{InvocationCounter, Memory};
+    /// let mut doubler = InvocationCounter::new(Memory::new(|x: &u32| x * 2));
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.invocation_count(&2), 2);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(count) = self.counts.get_mut(input) {
+            *count += 1;
+        } else {
+            self.counts.insert(input.clone(), 1);
+        }
+        self.memory.resolve(input)
+    }
+
+    /// How many times the input has been resolved.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{InvocationCounter, Memory};
+    /// let mut doubler = InvocationCounter::new(Memory::new(|x: &u32| x * 2));
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.invocation_count(&2), 1);
+    /// assert_eq!(doubler.invocation_count(&3), 0);
+    /// ```
+    pub fn invocation_count(&self, key: &TIn) -> u64
+    where
+        TIn: Eq + Hash,
+    {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_hits_and_misses() {
+        let mut counter = InvocationCounter::new(Memory::new(|key: &u32| key * 2));
+        assert_eq!(counter.resolve(&1), 2);
+        assert_eq!(counter.resolve(&2), 4);
+        assert_eq!(counter.resolve(&1), 2);
+        assert_eq!(counter.resolve(&1), 2);
+
+        assert_eq!(counter.invocation_count(&1), 3);
+        assert_eq!(counter.invocation_count(&2), 1);
+        assert_eq!(counter.invocation_count(&3), 0);
+    }
+
+    #[test]
+    fn it_keeps_caching_semantics() {
+        let mut resolver_calls = 0;
+        let mut counter = InvocationCounter::new(Memory::new(|key: &u32| {
+            resolver_calls += 1;
+            key * 2
+        }));
+        for _ in 0..5 {
+            assert_eq!(counter.resolve(&2), 4);
+        }
+        assert_eq!(counter.invocation_count(&2), 5);
+        drop(counter);
+
+        assert_eq!(resolver_calls, 1);
+    }
+}
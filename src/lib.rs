@@ -14,8 +14,111 @@
 #![deny(clippy::complexity)]
 #![warn(clippy::style)]
 
+// Lets the macros refer to `::souvenir` from inside the crate too
+#[cfg(feature = "proc-macros")]
+extern crate self as souvenir;
+
+mod access_tracking;
+mod adaptive;
+mod as_key;
+mod background_refresh;
+mod bounded;
+mod broadcast;
+mod cached;
+mod chained;
+mod circuit_breaker;
+mod cold_start;
+mod concurrency_limited;
+mod debug;
+mod defaulted;
+mod dependency;
+mod entry;
+mod fallible_memory;
+mod fallible_recall;
+mod frozen;
+mod generational;
+mod hashed_key;
+mod hooked;
+mod invocation_counter;
+mod memoize;
 mod memory;
+mod multi_key;
+mod namespaced;
+mod observed;
+mod pool;
+mod pure;
+mod rate_limited;
+mod read_through;
 mod recall;
+mod retry;
+mod rng;
+mod sampling;
+mod shadow;
+#[cfg(feature = "size-estimator")]
+mod size;
+mod sliding_window;
+mod soft;
+mod split;
+#[cfg(feature = "stats")]
+mod stats;
+mod sync;
+#[cfg(test)]
+mod test_clock;
+mod then;
+mod timestamped;
+mod transparent;
+mod two_queue;
+mod update;
+mod view;
+mod write_back;
 
-pub use memory::Memory;
+pub use access_tracking::AccessTrackingMemory;
+pub use adaptive::AdaptiveMemory;
+pub use as_key::AsKey;
+pub use background_refresh::BackgroundRefreshMemory;
+pub use bounded::{BoundedKeyMemory, CapacityExceeded};
+pub use broadcast::{BroadcastRecall, BroadcastReceiver};
+pub use cached::Cached;
+pub use chained::ChainedRecall;
+pub use circuit_breaker::{CircuitBreakerError, CircuitBreakerMemory, CircuitState};
+pub use cold_start::ColdStartMemory;
+pub use concurrency_limited::ConcurrencyLimitedMemory;
+pub use debug::DebugMemory;
+pub use defaulted::DefaultedRecall;
+pub use dependency::DependencyMemory;
+pub use entry::{EntryRef, OccupiedEntryRef, VacantEntryRef};
+pub use fallible_memory::FallibleMemory;
+pub use fallible_recall::FallibleRecall;
+pub use frozen::FrozenMemory;
+pub use generational::GenerationalMemory;
+pub use hashed_key::HashedKeyMemory;
+pub use hooked::HookedMemory;
+pub use invocation_counter::InvocationCounter;
+pub use memoize::Memoize;
+pub use memory::{CacheStatus, Memory, TimeoutError};
+pub use multi_key::MultiKeyMemory;
+pub use namespaced::NamespacedMemory;
+pub use observed::ObservedRecall;
+pub use pool::{MemoryPool, PooledMemory};
+pub use pure::PureMemory;
+pub use rate_limited::{RateLimited, RateLimitedMemory};
+pub use read_through::{FallbackMemory, ReadThroughMemory};
 pub use recall::Recall;
+pub use retry::RetryRecall;
+pub use sampling::SamplingMemory;
+pub use shadow::ShadowMemory;
+#[cfg(feature = "size-estimator")]
+pub use size::MemSize;
+pub use sliding_window::SlidingWindowMemory;
+pub use soft::SoftMemory;
+#[cfg(feature = "proc-macros")]
+pub use souvenir_macros::{memoize, once, Cached};
+pub use split::{MemoryReader, MemoryWriter, SplitMemory};
+pub use sync::SyncMemory;
+pub use then::ThenRecall;
+pub use timestamped::TimestampedMemory;
+pub use transparent::TransparentMemory;
+pub use two_queue::TwoQueueMemory;
+pub use update::UpdateToken;
+pub use view::MemoryView;
+pub use write_back::WriteBackMemory;
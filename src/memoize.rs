@@ -0,0 +1,49 @@
+use crate::memory::Memory;
+use std::hash::Hash;
+
+/// Anything resolving inputs to values the way a [`Memory`] does.
+///
+/// Lets code take either a [`Memory`] or a
+/// [`TransparentMemory`](crate::TransparentMemory), so caching can be switched off
+/// without touching the call sites.
+///
+/// # Examples
+/// ```
+/// use souvenir::{Memoize, Memory};
+///
+/// fn total(memory: &mut impl Memoize<u32, u32>) -> u32 {
+///     [1, 2, 1].iter().map(|x| memory.resolve(x)).sum()
+/// }
+///
+/// let cache = std::env::var("NO_CACHE").is_err();
+/// let mut doubler: Box<dyn Memoize<u32, u32>> = if cache {
+///     Box::new(Memory::new(|x: &u32| x * 2))
+/// } else {
+///     Box::new(Memory::transparent(|x: &u32| x * 2))
+/// };
+/// assert_eq!(total(&mut doubler), 8);
+/// ```
+pub trait Memoize<TIn, TOut> {
+    /// Get the value of this input.
+    fn resolve(&mut self, input: &TIn) -> TOut;
+}
+
+impl<T, TIn, TOut> Memoize<TIn, TOut> for Memory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+    TIn: Clone + Eq + Hash,
+    TOut: Clone,
+{
+    fn resolve(&mut self, input: &TIn) -> TOut {
+        Self::resolve(self, input)
+    }
+}
+
+impl<TIn, TOut, M> Memoize<TIn, TOut> for Box<M>
+where
+    M: Memoize<TIn, TOut> + ?Sized,
+{
+    fn resolve(&mut self, input: &TIn) -> TOut {
+        (**self).resolve(input)
+    }
+}
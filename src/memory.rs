@@ -1,10 +1,92 @@
-use std::{collections::HashMap, hash::Hash};
+use crate::access_tracking::AccessTrackingMemory;
+use crate::adaptive::AdaptiveMemory;
+use crate::as_key::AsKey;
+use crate::bounded::BoundedKeyMemory;
+use crate::cold_start::ColdStartMemory;
+use crate::debug::DebugMemory;
+use crate::entry::EntryRef;
+use crate::frozen::FrozenMemory;
+use crate::hashed_key::HashedKeyMemory;
+use crate::hooked::HookedMemory;
+use crate::namespaced::NamespacedMemory;
+use crate::read_through::{FallbackMemory, ReadThroughMemory};
+use crate::rng::Rng;
+use crate::sampling::SamplingMemory;
+use crate::shadow::ShadowMemory;
+#[cfg(feature = "size-estimator")]
+use crate::size::MemSize;
+#[cfg(feature = "stats")]
+use crate::stats::Stats;
+use crate::transparent::TransparentMemory;
+use crate::update::UpdateToken;
+use crate::view::MemoryView;
+use crate::write_back::WriteBackMemory;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+#[cfg(any(feature = "stats", feature = "rayon"))]
+use std::time::Instant;
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{self, Debug},
+    hash::Hash,
+    io::{self, Write},
+    mem,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// Whether an input is already present in a memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// The input has a cached value.
+    Hit,
+    /// The input would have to be resolved.
+    Miss,
+}
+
+/// The error returned when a resolver doesn't finish in time.
+///
+/// Returned by [`Memory::resolve_with_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutError {
+    /// How long the resolver was given.
+    pub timeout: Duration,
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "resolver timed out after {:?}", self.timeout)
+    }
+}
+
+impl Error for TimeoutError {}
 
 /// A general purpose structure for memoization.
 /// Useful when you will be memoization multiple data points.
+///
+/// A `Memory` is `Send` or `Sync` exactly when its resolver, inputs and outputs are.
+/// A resolver capturing something like an `Rc` keeps the memory on its thread:
+///
+/// ```compile_fail
+/// use souvenir::Memory;
+/// use std::rc::Rc;
+/// let offset = Rc::new(1);
+/// let mut memory = Memory::new(move |x: &u32| x + *offset);
+/// std::thread::spawn(move || memory.resolve(&2));
+/// ```
+///
+/// Resolving needs `&mut self`, so sharing one memory between threads
+/// takes a lock, such as `Arc<Mutex<Memory<..>>>`.
 pub struct Memory<T, TIn, TOut> {
-    remember: T,
-    values: HashMap<TIn, TOut>,
+    pub(crate) remember: T,
+    pub(crate) values: HashMap<TIn, TOut>,
+    #[cfg(feature = "ordering")]
+    pub(crate) order: Vec<TIn>,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: Stats<TIn>,
 }
 
 impl<T, TIn, TOut> Memory<T, TIn, TOut>
@@ -34,6 +116,10 @@ where
         Self {
             remember,
             values: HashMap::new(),
+            #[cfg(feature = "ordering")]
+            order: Vec::new(),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
         }
     }
 
@@ -60,73 +146,3052 @@ where
         TOut: Clone,
     {
         if let Some(value) = self.values.get(input) {
-            value.clone()
-        } else {
-            let result = (self.remember)(input);
-            self.values.insert(input.clone(), result.clone());
-            result
+            return value.clone();
         }
+        let result = self.compute(input);
+        self.store(input.clone(), result.clone());
+        result
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Pair the memory with an authoritative data source.
+    ///
+    /// On a miss in the memory, the source is queried first.
+    /// Only when the source also has nothing is the resolver run,
+    /// and its result is handed to `writer` to fill the source.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// use std::{cell::RefCell, collections::HashMap};
+    /// let store = RefCell::new(HashMap::from([(1, 100)]));
+    /// let mut lookup = Memory::new(|x: &u32| x * 2).read_through(
+    ///     |x: &u32| store.borrow().get(x).copied(),
+    ///     |x: &u32, value: &u32| {
+    ///         store.borrow_mut().insert(*x, *value);
+    ///     },
+    /// );
+    ///
+    /// assert_eq!(lookup.resolve(&1), 100); // Found in the source
+    /// assert_eq!(lookup.resolve(&2), 4); // Computed by the resolver
+    /// drop(lookup);
+    /// assert_eq!(store.borrow()[&2], 4); // And written back
+    /// ```
+    pub const fn read_through<S, W>(
+        self,
+        source: S,
+        writer: W,
+    ) -> ReadThroughMemory<T, S, W, TIn, TOut>
+    where
+        S: FnMut(&TIn) -> Option<TOut>,
+        W: FnMut(&TIn, &TOut),
+    {
+        ReadThroughMemory::new(self, source, writer)
+    }
 
-    #[test]
-    fn it_works_with_primatives() {
-        let mut doubler = Memory::new(|key: &u32| key * 2);
-        assert_eq!(doubler.resolve(&2), 4);
+    /// Scope every key of the memory under a namespace.
+    ///
+    /// Entries are grouped per namespace, so the same input
+    /// resolved under two namespaces never collides.
+    /// Entries already in the memory are moved under the namespace.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut users = Memory::new(|id: &u32| format!("user-{id}")).namespace("users");
+    /// assert_eq!(users.resolve(&1), "user-1");
+    /// ```
+    pub fn namespace(self, ns: impl Into<String>) -> NamespacedMemory<T, TIn, TOut>
+    where
+        TIn: Eq + Hash,
+    {
+        let ns = ns.into();
+        let values = HashMap::from([(ns.clone(), self.values)]);
+        NamespacedMemory::new(self.remember, ns, values)
     }
 
-    #[test]
-    fn it_works_with_strings() {
-        let mut doubler = Memory::new(|key: &String| key.to_string() + "test");
-        let test_input = String::from("test");
-        assert_eq!(doubler.resolve(&test_input), "testtest");
+    /// Search the memory for the previous result of this input,
+    /// using a one-off resolver on a miss.
+    ///
+    /// The fallback replaces the stored resolver for this call only,
+    /// and its result is cached like any other.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve_with_fallback(&2, |x| x * 100), 200);
+    /// assert_eq!(doubler.resolve(&2), 200); // Cached from the fallback
+    /// ```
+    pub fn resolve_with_fallback<F>(&mut self, input: &TIn, fallback: F) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+        F: FnOnce(&TIn) -> TOut,
+    {
+        if let Some(value) = self.values.get(input) {
+            return value.clone();
+        }
+        let result = fallback(input);
+        self.store(input.clone(), result.clone());
+        result
     }
 
-    #[test]
-    fn it_only_runs_once() {
-        let mut counter = 0;
-        let tool = |key: &u32| {
-            counter += 1;
-            key * 2
-        };
+    /// Look up the previous result of this input, or the default value.
+    ///
+    /// Neither the resolver is called nor anything inserted on a miss.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.get_or_default(&2), 0);
+    ///
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.get_or_default(&2), 4);
+    /// ```
+    pub fn get_or_default(&self, input: &TIn) -> TOut
+    where
+        TIn: Eq + Hash,
+        TOut: Clone + Default,
+    {
+        self.values.get(input).cloned().unwrap_or_default()
+    }
 
-        let mut doubler = Memory::new(tool);
-        assert_eq!(doubler.resolve(&2), 4); // counter == 1
-        assert_eq!(doubler.resolve(&2), 4); // counter == 1
-        assert_eq!(doubler.resolve(&3), 6); // counter == 2
-        assert_eq!(counter, 2);
+    /// Look up the previous result of this input,
+    /// storing the default value on a miss.
+    ///
+    /// The resolver is not called.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(*doubler.get_or_insert_default(2), 0);
+    /// assert_eq!(doubler.resolve(&2), 0); // The default was cached
+    /// ```
+    pub fn get_or_insert_default(&mut self, input: TIn) -> &TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Default,
+    {
+        #[cfg(feature = "ordering")]
+        self.record_order(&input);
+        self.values.entry(input).or_default()
     }
 
-    #[test]
-    fn it_works_with_structs() {
-        #[derive(Hash, PartialEq, Eq, Clone)]
-        struct User {
-            scores: Vec<u32>,
-        }
-        impl User {
-            fn total_score(&self) -> u32 {
-                self.scores.iter().sum()
+    /// Store the default value for each of these inputs that isn't cached yet.
+    ///
+    /// The resolver is not called, and cached values are left alone.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.apply_default([1, 2]);
+    /// assert_eq!(doubler.resolve(&1), 2);
+    /// assert_eq!(doubler.resolve(&2), 0);
+    /// ```
+    pub fn apply_default(&mut self, inputs: impl IntoIterator<Item = TIn>)
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Default,
+    {
+        for input in inputs {
+            if !self.values.contains_key(&input) {
+                self.store(input, TOut::default());
             }
         }
+    }
 
-        let mut trigger = 0;
-        let tool = |user: &User| -> u32 {
-            trigger += 1;
-            user.total_score()
-        };
-        let test_user = User {
-            scores: Vec::from([1, 2, 3]),
-        };
+    /// Check whether resolving this input would hit the cache.
+    ///
+    /// Nothing is computed or stored,
+    /// which makes it useful to confirm what a benchmark is exercising.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{CacheStatus, Memory};
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.probe(&2), CacheStatus::Miss);
+    ///
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.probe(&2), CacheStatus::Hit);
+    /// ```
+    pub fn probe(&self, input: &TIn) -> CacheStatus
+    where
+        TIn: Eq + Hash,
+    {
+        if self.values.contains_key(input) {
+            CacheStatus::Hit
+        } else {
+            CacheStatus::Miss
+        }
+    }
 
-        let mut memory = Memory::new(tool);
-        let user1_score = memory.resolve(&test_user);
-        let user2_score = memory.resolve(&test_user);
-        assert_eq!(user1_score, 6);
-        assert_eq!(user1_score, user2_score);
-        assert_eq!(trigger, 1);
+    /// Run the resolver for this input, bypassing the memory entirely.
+    ///
+    /// The cache is neither consulted nor updated.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve_uncached(&2), 4);
+    /// ```
+    pub fn resolve_uncached(&mut self, input: &TIn) -> TOut {
+        (self.remember)(input)
+    }
+
+    /// Search the memory for the previous result of this input,
+    /// storing the given value on a miss.
+    ///
+    /// The resolver is not called, and the value is dropped on a hit.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(*doubler.resolve_or_insert(2, 5), 5);
+    /// assert_eq!(*doubler.resolve_or_insert(2, 6), 5); // Already cached
+    /// ```
+    pub fn resolve_or_insert(&mut self, input: TIn, value: TOut) -> &TOut
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        #[cfg(feature = "ordering")]
+        self.record_order(&input);
+        self.values.entry(input).or_insert(value)
+    }
+
+    /// Search the memory for the previous result of a borrowed form of the input
+    ///
+    /// Like [`HashMap::get`], the lookup accepts any form the input can be borrowed as,
+    /// e.g. a `&str` for `String` inputs.
+    /// An owned input is only built on a miss, to be passed to the resolver and stored.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// assert_eq!(*lengths.resolve_ref_borrow("souvenir"), 8);
+    /// ```
+    pub fn resolve_ref_borrow<Q>(&mut self, input: &Q) -> &TOut
+    where
+        TIn: Borrow<Q> + for<'a> From<&'a Q> + Clone + Eq + Hash,
+        Q: Eq + Hash + ?Sized,
+    {
+        if !self.values.contains_key(input) {
+            let key = TIn::from(input);
+            let result = self.compute(&key);
+            self.store(key, result);
+        }
+        &self.values[input]
+    }
+
+    /// Search the memory for the previous result of any form of the input implementing [`AsKey`]
+    ///
+    /// Owned and borrowed inputs can be mixed, e.g. `&str` and `String` for `String` inputs.
+    /// The owned input is only built on a miss.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// assert_eq!(*lengths.resolve_key("souvenir"), 8);
+    /// assert_eq!(*lengths.resolve_key(&String::from("souvenir")), 8); // Didn't recalculate
+    ///
+    /// let mut sums = Memory::new(|x: &Vec<u8>| x.iter().sum::<u8>());
+    /// assert_eq!(*sums.resolve_key(&[1, 2, 3][..]), 6);
+    /// ```
+    pub fn resolve_key<Q>(&mut self, input: &Q) -> &TOut
+    where
+        TIn: Borrow<Q> + Clone + Eq + Hash,
+        Q: AsKey<TIn> + ?Sized,
+    {
+        if !self.values.contains_key(input) {
+            let key = input.to_key();
+            let result = self.compute(&key);
+            self.store(key, result);
+        }
+        &self.values[input]
+    }
+
+    /// Only cache a random fraction of the computed values.
+    ///
+    /// With a `rate` of `0.25`, about one in four misses stores its result.
+    ///
+    /// # Panics
+    /// Panics if `rate` is not between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_sample_rate(0.25);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn with_sample_rate(self, rate: f64) -> SamplingMemory<T, TIn, TOut> {
+        SamplingMemory::new(self, rate)
+    }
+
+    /// Report every hit and miss of the memory to stderr, in debug builds.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).debug();
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn debug(self) -> DebugMemory<T, TIn, TOut>
+    where
+        TIn: Debug,
+        TOut: Debug,
+    {
+        DebugMemory::new(self, io::stderr())
+    }
+
+    /// Report every hit and miss of the memory to the given sink, in debug builds.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).debug_to(Vec::new());
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub const fn debug_to<W>(self, sink: W) -> DebugMemory<T, TIn, TOut, W>
+    where
+        TIn: Debug,
+        TOut: Debug,
+        W: Write,
+    {
+        DebugMemory::new(self, sink)
+    }
+
+    /// Check a read-only store before calling the resolver on a miss.
+    ///
+    /// This is a [`read_through`](Self::read_through) with a stateless fallback,
+    /// such as a preloaded static table. Nothing is written back to it.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let table = [(1, 100)];
+    /// let mut lookup = Memory::new(|x: &u32| x * 2).with_read_fallback(|x: &u32| {
+    ///     table.iter().find(|(key, _)| key == x).map(|(_, value)| *value)
+    /// });
+    ///
+    /// assert_eq!(lookup.resolve(&1), 100);
+    /// assert_eq!(lookup.resolve(&2), 4);
+    /// ```
+    pub const fn with_read_fallback<F>(self, fallback: F) -> FallbackMemory<T, F, TIn, TOut>
+    where
+        F: Fn(&TIn) -> Option<TOut>,
+    {
+        self.read_through(fallback, |_, _| {})
+    }
+
+    /// Batch newly computed entries for a persistent store, written on demand.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut writes = 0;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).write_back(|_: &[(u32, u32)]| writes += 1);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    /// doubler.flush();
+    /// drop(doubler);
+    /// assert_eq!(writes, 1);
+    /// ```
+    pub const fn write_back<W>(self, writer: W) -> WriteBackMemory<T, W, TIn, TOut>
+    where
+        W: FnMut(&[(TIn, TOut)]),
+    {
+        WriteBackMemory::new(self, writer)
+    }
+
+    /// Ask a hook for permission before every resolver call.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_pre_resolve_hook(|x| {
+    ///     println!("resolving {x}");
+    ///     true
+    /// });
+    /// assert_eq!(doubler.resolve(&2), Some(4));
+    /// ```
+    pub const fn with_pre_resolve_hook<H>(self, hook: H) -> HookedMemory<T, H, TIn, TOut>
+    where
+        H: FnMut(&TIn) -> bool,
+    {
+        HookedMemory::new(self, hook)
+    }
+
+    /// Compare the resolver against a shadow resolver on every miss, caching only the primary result.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).shadow(|x: &u32| x << 1);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.mismatches(), 0);
+    /// ```
+    pub fn shadow<U>(self, shadow: U) -> ShadowMemory<T, U, TIn, TOut>
+    where
+        U: FnMut(&TIn) -> TOut,
+    {
+        ShadowMemory::new(self, shadow)
+    }
+
+    /// Remember when each input was last resolved, to find or evict idle entries.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_access_tracking();
+    /// doubler.resolve(&2);
+    /// assert!(doubler.idle_for(&2).is_some());
+    /// ```
+    pub fn with_access_tracking(self) -> AccessTrackingMemory<T, TIn, TOut> {
+        AccessTrackingMemory::new(self)
+    }
+
+    /// Identify inputs by the given hash, rather than by their own `Hash` and `Eq`.
+    ///
+    /// Cached entries are carried over under their new hash.
+    /// Inputs hashing to the same value share one entry, see [`HashedKeyMemory`].
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &(u32, &str)| x.0 * 2)
+    ///     .with_key_hasher(|x: &(u32, &str)| u64::from(x.0));
+    /// assert_eq!(doubler.resolve(&(2, "unit")), 4);
+    /// assert_eq!(doubler.resolve(&(2, "test")), 4); // Label is ignored
+    /// ```
+    pub fn with_key_hasher<H>(self, hasher: H) -> HashedKeyMemory<T, H, TIn, TOut>
+    where
+        H: Fn(&TIn) -> u64,
+    {
+        let values = self
+            .values
+            .into_iter()
+            .map(|(input, value)| (hasher(&input), value))
+            .collect();
+        HashedKeyMemory::new(self.remember, hasher, values)
+    }
+
+    /// Create a memory that starts resolving the given keys on a background thread.
+    ///
+    /// The first access to those keys is then a plain lookup,
+    /// as long as the background thread got to them in time.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::cold_start(|x: &u32| x * 2, vec![1, 2, 3]);
+    /// assert_eq!(doubler.resolve(&3), 6);
+    /// ```
+    pub fn cold_start(remember: T, keys: Vec<TIn>) -> ColdStartMemory<T, TIn, TOut>
+    where
+        T: Fn(&TIn) -> TOut + Send + Sync + 'static,
+        TIn: Eq + Hash + Send + 'static,
+        TOut: Send + 'static,
+    {
+        ColdStartMemory::new(remember, keys)
+    }
+
+    /// Create a memory that doesn't cache, calling the resolver on every resolve.
+    ///
+    /// Both implement [`Memoize`](crate::Memoize),
+    /// so code written against it can be run with and without the cache.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::transparent(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub const fn transparent(remember: T) -> TransparentMemory<T, TIn, TOut> {
+        TransparentMemory::new(remember)
+    }
+
+    /// Create a memory that looks up entries with a linear scan while it holds
+    /// at most `linear_threshold` of them, and switches to hashing after that.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::adaptive(|x: &u32| x * 2, 8);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub const fn adaptive(remember: T, linear_threshold: usize) -> AdaptiveMemory<T, TIn, TOut> {
+        AdaptiveMemory::new(remember, linear_threshold)
+    }
+
+    /// Create a memory that holds at most `max` keys.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::with_max_keys(|x: &u32| x * 2, 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    /// assert!(doubler.try_resolve(&3).is_err());
+    /// ```
+    pub fn with_max_keys(remember: T, max: usize) -> BoundedKeyMemory<T, TIn, TOut> {
+        BoundedKeyMemory::new(Self::new(remember), max)
+    }
+
+    /// Create a memory with a random fraction of the given keys already resolved.
+    ///
+    /// With a `ratio` of `0.25`, a quarter of the keys (rounded) are resolved,
+    /// which is handy to benchmark a partially warm cache.
+    ///
+    /// # Panics
+    /// Panics if `ratio` is not between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let doubler = Memory::with_warm_ratio(|x: &u32| x * 2, 0..100, 0.25);
+    /// assert_eq!(doubler.iter().count(), 25);
+    /// ```
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn with_warm_ratio<I>(remember: T, all_keys: I, ratio: f64) -> Self
+    where
+        I: IntoIterator<Item = TIn>,
+        TIn: Clone + Eq + Hash,
+    {
+        assert!(
+            (0.0..=1.0).contains(&ratio),
+            "warm ratio must be between 0.0 and 1.0, got {ratio}"
+        );
+        let mut keys: Vec<_> = all_keys.into_iter().collect();
+        // The ratio is within [0, 1], so the count is within [0, len]
+        let count = (ratio * keys.len() as f64).round() as usize;
+
+        // Shuffle the first `count` keys into place, then resolve them
+        let mut rng = Rng::new();
+        for i in 0..count {
+            let j = i + rng.next_index(keys.len() - i);
+            keys.swap(i, j);
+        }
+
+        let mut memory = Self::new(remember);
+        for key in keys.into_iter().take(count) {
+            let value = memory.compute(&key);
+            memory.store(key, value);
+        }
+        memory
+    }
+
+    /// Search the memory for the previous result of this input,
+    /// giving up on the resolver if it takes longer than `timeout`.
+    ///
+    /// The resolver is cloned onto another thread for the call.
+    /// A resolver that times out keeps running there, but its result is dropped.
+    ///
+    /// # Errors
+    /// Returns [`TimeoutError`] if the resolver didn't finish in time.
+    /// Nothing is cached in that case.
+    ///
+    /// # Panics
+    /// Re-raises the panic of the resolver, if it panics within the timeout.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// use std::time::Duration;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve_with_timeout(&2, Duration::from_secs(1)), Ok(4));
+    /// ```
+    pub fn resolve_with_timeout(
+        &mut self,
+        input: &TIn,
+        timeout: Duration,
+    ) -> Result<TOut, TimeoutError>
+    where
+        T: Clone + Send + 'static,
+        TIn: Clone + Eq + Hash + Send + 'static,
+        TOut: Clone + Send + 'static,
+    {
+        if let Some(value) = self.values.get(input) {
+            return Ok(value.clone());
+        }
+
+        let result = self.measure(input, |remember, input| {
+            let mut remember = remember.clone();
+            let input = input.clone();
+            let (sender, receiver) = mpsc::channel();
+            let worker = thread::spawn(move || {
+                // The receiver is gone if the call timed out
+                let _ = sender.send(remember(&input));
+            });
+            match receiver.recv_timeout(timeout) {
+                Ok(value) => Ok(value),
+                Err(mpsc::RecvTimeoutError::Disconnected) => match worker.join() {
+                    Err(panic) => std::panic::resume_unwind(panic),
+                    Ok(()) => unreachable!("the worker sends a value before exiting"),
+                },
+                Err(mpsc::RecvTimeoutError::Timeout) => Err(TimeoutError { timeout }),
+            }
+        })?;
+        self.store(input.clone(), result.clone());
+        Ok(result)
+    }
+
+    /// Resolve every given input, returning all of their values.
+    ///
+    /// Hits come from the memory, misses are resolved and stored.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// let values = doubler.resolve_all([1, 2, 3]);
+    /// assert_eq!(values[&3], 6);
+    /// ```
+    pub fn resolve_all(&mut self, inputs: impl IntoIterator<Item = TIn>) -> HashMap<TIn, TOut>
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        inputs
+            .into_iter()
+            .map(|input| {
+                let value = self.resolve(&input);
+                (input, value)
+            })
+            .collect()
+    }
+
+    /// Resolve every input of the slice, returning their values in the same order.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve_sequence(&[3, 1, 3]), [6, 2, 6]);
+    /// ```
+    pub fn resolve_sequence(&mut self, inputs: &[TIn]) -> Vec<TOut>
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        inputs.iter().map(|input| self.resolve(input)).collect()
+    }
+
+    /// Resolve the given inputs one at a time, as the iterator is advanced.
+    ///
+    /// Nothing is resolved until the first call to `next`,
+    /// so prefetching can be interleaved with other work.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// let mut prefetch = doubler.prefetch_lazy(vec![1, 2, 3]);
+    /// assert_eq!(prefetch.next(), Some(2));
+    /// // Do something else
+    /// assert_eq!(prefetch.collect::<Vec<_>>(), [4, 6]);
+    /// ```
+    pub fn prefetch_lazy(&mut self, inputs: Vec<TIn>) -> impl Iterator<Item = TOut> + '_
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        inputs.into_iter().map(|input| self.resolve(&input))
+    }
+
+    /// Turn the memory into a plain closure, which resolves through the memory.
+    ///
+    /// Useful to hand memoization to an API expecting a function.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).into_fn();
+    /// assert_eq!(doubler(&2), 4);
+    /// ```
+    pub fn into_fn(mut self) -> impl FnMut(&TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        move |input: &TIn| self.resolve(input)
+    }
+
+    /// Replace the resolver, keeping every cached entry.
+    ///
+    /// The new resolver only runs for future misses.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let mut tripler = doubler.transform(|x: &u32| x * 3);
+    /// assert_eq!(tripler.resolve(&2), 4); // Still cached
+    /// assert_eq!(tripler.resolve(&3), 9);
+    /// ```
+    pub fn transform<U>(self, new_resolver: U) -> Memory<U, TIn, TOut>
+    where
+        U: FnMut(&TIn) -> TOut,
+    {
+        Memory {
+            remember: new_resolver,
+            values: self.values,
+            #[cfg(feature = "ordering")]
+            order: self.order,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+        }
+    }
+
+    /// Wrap the resolver into a new one, keeping every cached entry.
+    ///
+    /// Useful to add logging or validation around the resolver already in place.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let mut logged = doubler.map_resolver(|mut resolve| {
+    ///     move |x: &u32| {
+    ///         println!("resolving {x}");
+    ///         resolve(x)
+    ///     }
+    /// });
+    /// assert_eq!(logged.resolve(&2), 4); // Still cached
+    /// assert_eq!(logged.resolve(&3), 6);
+    /// ```
+    pub fn map_resolver<U, F>(self, f: F) -> Memory<U, TIn, TOut>
+    where
+        U: FnMut(&TIn) -> TOut,
+        F: FnOnce(T) -> U,
+    {
+        Memory {
+            remember: f(self.remember),
+            values: self.values,
+            #[cfg(feature = "ordering")]
+            order: self.order,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+        }
+    }
+
+    /// Convert every value into another type, including those computed later.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let mut wide = doubler.coerce::<u64>();
+    /// assert_eq!(wide.resolve(&2), 4_u64);
+    /// assert_eq!(wide.resolve(&3), 6_u64);
+    /// ```
+    pub fn coerce<TOut2>(self) -> Memory<impl FnMut(&TIn) -> TOut2, TIn, TOut2>
+    where
+        TIn: Eq + Hash,
+        TOut2: From<TOut>,
+    {
+        let mut remember = self.remember;
+        Memory {
+            remember: move |input: &TIn| TOut2::from(remember(input)),
+            values: self
+                .values
+                .into_iter()
+                .map(|(key, value)| (key, TOut2::from(value)))
+                .collect(),
+            #[cfg(feature = "ordering")]
+            order: self.order,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+        }
+    }
+
+    /// Move the entries matching the predicate into a new memory,
+    /// sharing a clone of the resolver.
+    ///
+    /// Only the entries not matching the predicate stay in this memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    ///
+    /// let evens = doubler.split(|x| x % 2 == 0);
+    /// assert_eq!(evens.iter().collect::<Vec<_>>(), [(&2, &4)]);
+    /// assert_eq!(doubler.iter().collect::<Vec<_>>(), [(&1, &2)]);
+    /// ```
+    #[must_use]
+    pub fn split<F>(&mut self, predicate: F) -> Self
+    where
+        T: Clone,
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+        F: Fn(&TIn) -> bool,
+    {
+        let (matching, rest) = std::mem::take(&mut self.values)
+            .into_iter()
+            .partition(|(key, _)| predicate(key));
+        self.values = rest;
+
+        #[cfg(feature = "ordering")]
+        let order = self
+            .order
+            .iter()
+            .filter(|input| matching.contains_key(*input))
+            .cloned()
+            .collect();
+        #[cfg(feature = "ordering")]
+        self.prune_order();
+        Self {
+            remember: self.remember.clone(),
+            values: matching,
+            #[cfg(feature = "ordering")]
+            order,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    /// Move every entry of another memory into this one, dropping its resolver.
+    ///
+    /// On conflicting inputs, the entries of this memory are kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    ///
+    /// let evens = doubler.split(|x| x % 2 == 0);
+    /// doubler.absorb(evens);
+    /// assert_eq!(doubler.iter().count(), 2);
+    /// ```
+    pub fn absorb(&mut self, other: Self)
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        for (key, value) in other.values {
+            if !self.values.contains_key(&key) {
+                self.store(key, value);
+            }
+        }
+    }
+
+    /// Call the resolver again for every cached input, replacing the stored values.
+    ///
+    /// Unlike clearing the memory, every value is refreshed right away.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut offset = 0;
+    /// let mut adder = Memory::new(|x: &u32| {
+    ///     offset += 10;
+    ///     x + offset
+    /// });
+    /// assert_eq!(adder.resolve(&1), 11);
+    ///
+    /// adder.replay();
+    /// assert_eq!(adder.resolve(&1), 21);
+    /// ```
+    pub fn replay(&mut self)
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        let inputs: Vec<TIn> = self.values.keys().cloned().collect();
+        for input in inputs {
+            let value = self.compute(&input);
+            self.values.insert(input, value);
+        }
+    }
+
+    /// Call the resolver for a cache miss.
+    pub(crate) fn compute(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        self.measure(input, |remember, input| remember(input))
+    }
+
+    /// Run `call` in place of the resolver, recording it in the stats.
+    fn measure<R>(&mut self, input: &TIn, call: impl FnOnce(&mut T, &TIn) -> R) -> R
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        #[cfg(feature = "stats")]
+        {
+            let start = Instant::now();
+            let result = call(&mut self.remember, input);
+            self.record_call(input, start.elapsed());
+            result
+        }
+        #[cfg(not(feature = "stats"))]
+        call(&mut self.remember, input)
+    }
+
+    /// Count a resolver call for this input in the stats.
+    #[cfg(feature = "stats")]
+    fn record_call(&mut self, input: &TIn, elapsed: Duration)
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        *self.stats.misses.entry(input.clone()).or_insert(0) += 1;
+        self.stats.resolver_calls += 1;
+        self.stats.resolver_time += elapsed;
+    }
+}
+
+impl<T, TIn, TOut> Memory<T, TIn, TOut> {
+    /// Iterate over every cached input and its value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let entries: Vec<_> = doubler.iter().collect();
+    /// assert_eq!(entries, [(&2, &4)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&TIn, &TOut)> {
+        self.values.iter()
+    }
+
+    /// Iterate over every cached input, with mutable access to its value.
+    ///
+    /// Useful for updating cached values in place.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// for (_, value) in doubler.iter_mut() {
+    ///     *value += 1;
+    /// }
+    /// assert_eq!(doubler.resolve(&2), 5);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&TIn, &mut TOut)> {
+        self.values.iter_mut()
+    }
+
+    /// Transform every cached input, keeping the cached values.
+    ///
+    /// The resolver is kept as-is,
+    /// so it must be able to handle the new input type to resolve further misses.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let remapped = doubler.map_keys(u64::from);
+    /// assert_eq!(remapped.iter().collect::<Vec<_>>(), [(&2_u64, &4)]);
+    /// ```
+    pub fn map_keys<TIn2, F>(self, f: F) -> Memory<T, TIn2, TOut>
+    where
+        TIn2: Clone + Eq + Hash,
+        F: Fn(TIn) -> TIn2,
+    {
+        Memory {
+            remember: self.remember,
+            values: self
+                .values
+                .into_iter()
+                .map(|(key, value)| (f(key), value))
+                .collect(),
+            #[cfg(feature = "ordering")]
+            order: self.order.into_iter().map(&f).collect(),
+            #[cfg(feature = "stats")]
+            stats: self.stats.map_keys(f),
+        }
+    }
+
+    /// Split the cached entries in two, consuming the memory.
+    ///
+    /// The first map holds the entries matching the predicate, the second holds the rest.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&5);
+    ///
+    /// let (hot, cold) = doubler.partition(|_, value| *value > 5);
+    /// assert_eq!(hot.get(&5), Some(&10));
+    /// assert_eq!(cold.get(&1), Some(&2));
+    /// ```
+    pub fn partition<F>(self, predicate: F) -> (HashMap<TIn, TOut>, HashMap<TIn, TOut>)
+    where
+        TIn: Eq + Hash,
+        F: Fn(&TIn, &TOut) -> bool,
+    {
+        self.values
+            .into_iter()
+            .partition(|(key, value)| predicate(key, value))
+    }
+
+    /// Get the entry of a borrowed form of the input, for in-place manipulation.
+    ///
+    /// The owned input is only built if a value gets inserted,
+    /// so a hit never allocates. The resolver is not called.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut lengths = Memory::new(|x: &String| x.len());
+    /// *lengths.entry_ref("unit").or_insert(0) += 1;
+    /// assert_eq!(lengths.resolve(&String::from("unit")), 1);
+    /// ```
+    pub fn entry_ref<'a, Q>(&'a mut self, input: &'a Q) -> EntryRef<'a, TIn, TOut, Q>
+    where
+        TIn: Borrow<Q> + Eq + Hash,
+        Q: Eq + Hash + ?Sized,
+    {
+        EntryRef::new(self, input)
+    }
+
+    /// Tentatively store a value for this input, until the update is committed or rolled back.
+    ///
+    /// The resolver is not called.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// let update = doubler.begin_update(2, 5);
+    /// // ... commit the change elsewhere first
+    /// update.commit();
+    /// assert_eq!(doubler.resolve(&2), 5);
+    /// ```
+    pub fn begin_update(&mut self, key: TIn, value: TOut) -> UpdateToken<'_, TIn, TOut>
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        UpdateToken::new(self, key, value)
+    }
+
+    /// Count the cached inputs, grouped by a projection of each input.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// for x in 0..5 {
+    ///     doubler.resolve(&x);
+    /// }
+    ///
+    /// let parity = doubler.count_by(|x| x % 2 == 0);
+    /// assert_eq!(parity[&true], 3);
+    /// assert_eq!(parity[&false], 2);
+    /// ```
+    pub fn count_by<K2, F>(&self, f: F) -> HashMap<K2, usize>
+    where
+        K2: Eq + Hash,
+        F: Fn(&TIn) -> K2,
+    {
+        self.values.keys().fold(HashMap::new(), |mut counts, key| {
+            *counts.entry(f(key)).or_insert(0) += 1;
+            counts
+        })
+    }
+
+    /// Estimate the number of bytes used by the cached values.
+    ///
+    /// Includes the space the backing map has reserved for values it doesn't hold yet.
+    /// Inputs are not counted.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut greeter = Memory::new(|name: &u32| format!("hello {name}"));
+    /// let empty = greeter.size_of_values();
+    ///
+    /// greeter.resolve(&1);
+    /// assert!(greeter.size_of_values() > empty);
+    /// ```
+    #[cfg(feature = "size-estimator")]
+    pub fn size_of_values(&self) -> usize
+    where
+        TOut: MemSize,
+    {
+        let reserved = (self.values.capacity() - self.values.len()) * std::mem::size_of::<TOut>();
+        reserved + self.values.values().map(MemSize::mem_size).sum::<usize>()
+    }
+
+    /// The `n` inputs with the largest estimated values, largest first.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut repeater = Memory::new(|n: &usize| "x".repeat(*n));
+    /// for n in [1, 100, 10] {
+    ///     repeater.resolve(&n);
+    /// }
+    ///
+    /// let largest: Vec<_> = repeater.largest_values(2).into_iter().map(|(n, _)| *n).collect();
+    /// assert_eq!(largest, [100, 10]);
+    /// ```
+    #[cfg(feature = "size-estimator")]
+    pub fn largest_values(&self, n: usize) -> Vec<(&TIn, usize)>
+    where
+        TOut: MemSize,
+    {
+        let mut sizes: Vec<_> = self
+            .values
+            .iter()
+            .map(|(key, value)| (key, value.mem_size()))
+            .collect();
+        sizes.sort_by(|(_, left), (_, right)| right.cmp(left));
+        sizes.truncate(n);
+        sizes
+    }
+
+    /// Find a cached entry matching the predicate.
+    ///
+    /// Entries are not ordered, so when several match, any one of them may be returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&5);
+    ///
+    /// assert_eq!(doubler.find(|_, value| *value > 5), Some((&5, &10)));
+    /// ```
+    pub fn find<F>(&self, predicate: F) -> Option<(&TIn, &TOut)>
+    where
+        F: Fn(&TIn, &TOut) -> bool,
+    {
+        self.values
+            .iter()
+            .find(|(key, value)| predicate(key, value))
+    }
+
+    /// Find every cached entry matching the predicate, in no particular order.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// for x in 0..10 {
+    ///     doubler.resolve(&x);
+    /// }
+    ///
+    /// assert_eq!(doubler.find_all(|_, value| *value >= 10).len(), 5);
+    /// ```
+    pub fn find_all<F>(&self, predicate: F) -> Vec<(&TIn, &TOut)>
+    where
+        F: Fn(&TIn, &TOut) -> bool,
+    {
+        self.values
+            .iter()
+            .filter(|(key, value)| predicate(key, value))
+            .collect()
+    }
+
+    /// The cached entry with the largest value.
+    ///
+    /// Ties are broken arbitrarily, as entries are not ordered.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.max_by_value(), None);
+    ///
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&5);
+    /// assert_eq!(doubler.max_by_value(), Some((&5, &10)));
+    /// ```
+    pub fn max_by_value(&self) -> Option<(&TIn, &TOut)>
+    where
+        TOut: Ord,
+    {
+        self.values.iter().max_by_key(|(_, value)| *value)
+    }
+
+    /// The cached entry with the smallest value.
+    ///
+    /// Ties are broken arbitrarily, as entries are not ordered.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.min_by_value(), None);
+    ///
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&5);
+    /// assert_eq!(doubler.min_by_value(), Some((&1, &2)));
+    /// ```
+    pub fn min_by_value(&self) -> Option<(&TIn, &TOut)>
+    where
+        TOut: Ord,
+    {
+        self.values.iter().min_by_key(|(_, value)| *value)
+    }
+
+    /// Drop the resolver, keeping the cached entries as a read-only memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let frozen = doubler.freeze();
+    /// assert_eq!(frozen.get(&2), Some(&4));
+    /// ```
+    pub fn freeze(self) -> FrozenMemory<TIn, TOut> {
+        FrozenMemory::new(self.values)
+    }
+
+    /// Remove every cached entry that is no longer live.
+    ///
+    /// This is a cleanup, not a ban: a removed input is resolved again
+    /// the next time it is requested.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    ///
+    /// doubler.gc(|key, _| *key != 1);
+    /// assert_eq!(doubler.iter().count(), 1);
+    /// ```
+    pub fn gc(&mut self, is_live: impl Fn(&TIn, &TOut) -> bool)
+    where
+        TIn: Eq + Hash,
+    {
+        self.values.retain(|key, value| is_live(key, value));
+        #[cfg(feature = "ordering")]
+        self.prune_order();
+    }
+
+    /// Replace the cached values of the inputs matching the predicate.
+    ///
+    /// The transformation takes ownership of the old value and returns the new one.
+    /// Values of other inputs are left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    ///
+    /// doubler.apply(|x| *x == 2, |_, value| value + 1);
+    /// assert_eq!(doubler.resolve(&1), 2);
+    /// assert_eq!(doubler.resolve(&2), 5);
+    /// ```
+    pub fn apply<F>(&mut self, predicate: impl Fn(&TIn) -> bool, mut f: F)
+    where
+        TIn: Eq + Hash,
+        F: FnMut(&TIn, TOut) -> TOut,
+    {
+        self.values = std::mem::take(&mut self.values)
+            .into_iter()
+            .map(|(key, value)| {
+                if predicate(&key) {
+                    let value = f(&key, value);
+                    (key, value)
+                } else {
+                    (key, value)
+                }
+            })
+            .collect();
+    }
+
+    /// The cached inputs, in the order they were first stored.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&3);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&3);
+    /// assert_eq!(doubler.access_order(), [&3, &1]);
+    /// ```
+    #[cfg(feature = "ordering")]
+    pub fn access_order(&self) -> Vec<&TIn> {
+        self.order.iter().collect()
+    }
+
+    /// The last `n` inputs to be cached, oldest first.
+    ///
+    /// Fewer are returned if the memory holds less than `n` inputs.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// for x in 0..5 {
+    ///     doubler.resolve(&x);
+    /// }
+    /// assert_eq!(doubler.recent(2), [3, 4]);
+    /// assert_eq!(doubler.recent(10).len(), 5);
+    /// ```
+    #[cfg(feature = "ordering")]
+    pub fn recent(&self, n: usize) -> &[TIn] {
+        &self.order[self.order.len().saturating_sub(n)..]
+    }
+
+    /// The entry cached before every other one still in the memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve_sequence(&[3, 1, 2]);
+    /// assert_eq!(doubler.first_resolved(), Some((&3, &6)));
+    /// ```
+    #[cfg(feature = "ordering")]
+    pub fn first_resolved(&self) -> Option<(&TIn, &TOut)>
+    where
+        TIn: Eq + Hash,
+    {
+        self.order
+            .iter()
+            .find_map(|input| self.values.get_key_value(input))
+    }
+
+    /// The entry cached after every other one still in the memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve_sequence(&[3, 1, 2]);
+    /// assert_eq!(doubler.last_resolved(), Some((&2, &4)));
+    /// ```
+    #[cfg(feature = "ordering")]
+    pub fn last_resolved(&self) -> Option<(&TIn, &TOut)>
+    where
+        TIn: Eq + Hash,
+    {
+        self.order
+            .iter()
+            .rev()
+            .find_map(|input| self.values.get_key_value(input))
+    }
+
+    /// The `n` inputs the resolver was called for the most, busiest first.
+    ///
+    /// An input is counted again each time it's resolved after being removed,
+    /// whether by [`Memory::gc`] or [`Memory::split`].
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    /// doubler.gc(|key, _| *key != 2);
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.top_n_misses(1), [(&2, 2)]);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn top_n_misses(&self, n: usize) -> Vec<(&TIn, u64)> {
+        let mut misses: Vec<_> = self
+            .stats
+            .misses
+            .iter()
+            .map(|(key, count)| (key, *count))
+            .collect();
+        misses.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        misses.truncate(n);
+        misses
+    }
+
+    /// Describe every cached entry, one `input → value` pair per line.
+    ///
+    /// Lines are sorted, so the description is stable across runs.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// doubler.resolve(&1);
+    /// assert_eq!(doubler.explain(), "1 → 2\n2 → 4");
+    /// ```
+    #[cfg(feature = "explain")]
+    pub fn explain(&self) -> String
+    where
+        TIn: Debug,
+        TOut: Debug,
+    {
+        let mut lines: Vec<_> = self
+            .values
+            .iter()
+            .map(|(input, value)| format!("{input:?} → {value:?}"))
+            .collect();
+        lines.sort();
+        lines.join("\n")
+    }
+
+    /// Describe the cached entry for this input, if there is one.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut greeter = Memory::new(|name: &String| format!("hello {name}"));
+    /// greeter.resolve(&String::from("unit"));
+    /// assert_eq!(
+    ///     greeter.explain_key(&String::from("unit")).as_deref(),
+    ///     Some(r#""unit" → "hello unit""#)
+    /// );
+    /// assert_eq!(greeter.explain_key(&String::from("test")), None);
+    /// ```
+    #[cfg(feature = "explain")]
+    pub fn explain_key(&self, input: &TIn) -> Option<String>
+    where
+        TIn: Debug + Eq + Hash,
+        TOut: Debug,
+    {
+        self.values
+            .get(input)
+            .map(|value| format!("{input:?} → {value:?}"))
+    }
+
+    /// Fold every cached entry into a single result.
+    ///
+    /// Entries are visited in no particular order.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve_all([1, 2, 3]);
+    /// assert_eq!(doubler.reduce(0, |total, (_, value)| total + value), 12);
+    /// ```
+    pub fn reduce<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, (&TIn, &TOut)) -> B,
+    {
+        self.values.iter().fold(init, f)
+    }
+
+    /// Take every entry out of the memory, sorted by the given key.
+    ///
+    /// Entries with the same key come out in no particular order,
+    /// so pick a key that's unique for a fully deterministic result.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve_all([3, 1, 2]);
+    /// assert_eq!(doubler.into_sorted_vec(|x, _| *x), [(1, 2), (2, 4), (3, 6)]);
+    /// ```
+    pub fn into_sorted_vec<K, F>(self, key_fn: F) -> Vec<(TIn, TOut)>
+    where
+        K: Ord,
+        F: Fn(&TIn, &TOut) -> K,
+    {
+        let mut entries: Vec<_> = self.values.into_iter().collect();
+        entries.sort_by_key(|(input, value)| key_fn(input, value));
+        entries
+    }
+
+    /// Check whether any cached entry holds this value.
+    ///
+    /// This scans every entry, so it's O(N) in the size of the memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// assert!(doubler.contains_value(&4));
+    /// assert!(!doubler.contains_value(&2));
+    /// ```
+    pub fn contains_value(&self, value: &TOut) -> bool
+    where
+        TOut: PartialEq,
+    {
+        self.values.values().any(|cached| cached == value)
+    }
+
+    /// Get every cached input whose value is this one, in no particular order.
+    ///
+    /// Like [`Memory::contains_value`], this scans every entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut parity = Memory::new(|x: &u32| x % 2);
+    /// parity.resolve_all([1, 2, 3]);
+    /// let mut odd = parity.keys_for_value(&1);
+    /// odd.sort();
+    /// assert_eq!(odd, [&1, &3]);
+    /// ```
+    pub fn keys_for_value(&self, value: &TOut) -> Vec<&TIn>
+    where
+        TOut: PartialEq,
+    {
+        self.values
+            .iter()
+            .filter(|(_, cached)| *cached == value)
+            .map(|(input, _)| input)
+            .collect()
+    }
+
+    /// Partition the cached entries by the group the classifier puts them in.
+    ///
+    /// Entries within a group are in no particular order.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve_all([1, 2, 3]);
+    /// let groups = doubler.group_by(|_, value| *value > 2);
+    /// assert_eq!(groups[&true].len(), 2);
+    /// assert_eq!(groups[&false], [(&1, &2)]);
+    /// ```
+    pub fn group_by<G, F>(&self, classifier: F) -> HashMap<G, Vec<(&TIn, &TOut)>>
+    where
+        G: Eq + Hash,
+        F: Fn(&TIn, &TOut) -> G,
+    {
+        let mut groups: HashMap<G, Vec<_>> = HashMap::new();
+        for (input, value) in &self.values {
+            groups
+                .entry(classifier(input, value))
+                .or_default()
+                .push((input, value));
+        }
+        groups
+    }
+
+    /// Count how many cached inputs share each value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut parity = Memory::new(|x: &u32| x % 2);
+    /// parity.resolve_all([1, 2, 3]);
+    /// let counts = parity.value_counts();
+    /// assert_eq!(counts[&1], 2);
+    /// assert_eq!(counts[&0], 1);
+    /// ```
+    pub fn value_counts(&self) -> HashMap<&TOut, usize>
+    where
+        TOut: Eq + Hash,
+    {
+        let mut counts = HashMap::new();
+        for value in self.values.values() {
+            *counts.entry(value).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Replace the cached value of this input, if it's still the expected one.
+    ///
+    /// Returns the old value on success, or the current value if it didn't match.
+    /// Nothing is stored for an input that isn't cached, and `new` is handed back.
+    ///
+    /// # Errors
+    /// Returns `Err(current)` if the cached value isn't `expected`,
+    /// or `Err(new)` if the input isn't cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.compare_exchange(&2, &4, 5), Ok(4));
+    /// assert_eq!(doubler.compare_exchange(&2, &4, 6), Err(5));
+    /// assert_eq!(doubler.resolve(&2), 5);
+    /// ```
+    pub fn compare_exchange(&mut self, key: &TIn, expected: &TOut, new: TOut) -> Result<TOut, TOut>
+    where
+        TIn: Eq + Hash,
+        TOut: Clone + PartialEq,
+    {
+        let Some(current) = self.values.get_mut(key) else {
+            return Err(new);
+        };
+        if current != expected {
+            return Err(current.clone());
+        }
+        Ok(mem::replace(current, new))
+    }
+
+    /// Replace the cached value of this input, whatever it was.
+    ///
+    /// Returns the old value, or `None` without storing anything if the input isn't cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.swap(&2, 5), Some(4));
+    /// assert_eq!(doubler.swap(&3, 5), None);
+    /// assert_eq!(doubler.resolve(&2), 5);
+    /// ```
+    pub fn swap(&mut self, key: &TIn, new: TOut) -> Option<TOut>
+    where
+        TIn: Eq + Hash,
+    {
+        self.values
+            .get_mut(key)
+            .map(|current| mem::replace(current, new))
+    }
+
+    /// Get mutable access to the cached values of two inputs at once.
+    ///
+    /// If both inputs are the same, only the first gets the value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve_all([1, 2]);
+    /// if let (Some(a), Some(b)) = doubler.get_two_mut(&1, &2) {
+    ///     std::mem::swap(a, b);
+    /// }
+    /// assert_eq!(doubler.resolve(&1), 4);
+    /// assert_eq!(doubler.resolve(&2), 2);
+    /// ```
+    pub fn get_two_mut(&mut self, a: &TIn, b: &TIn) -> (Option<&mut TOut>, Option<&mut TOut>)
+    where
+        TIn: Eq + Hash,
+    {
+        if a == b {
+            return (self.values.get_mut(a), None);
+        }
+        self.values.get_disjoint_mut([a, b]).into()
+    }
+
+    /// Move the cached value of an input to another input, without recomputing it.
+    ///
+    /// Any value already cached for `new` is overwritten.
+    /// The renamed entry keeps the place of `old` in the insertion order.
+    /// Returns whether `old` was cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// assert!(doubler.rename_key(&2, 3));
+    /// assert_eq!(doubler.resolve(&3), 4);
+    /// ```
+    pub fn rename_key(&mut self, old: &TIn, new: TIn) -> bool
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        if *old == new {
+            return self.values.contains_key(old);
+        }
+        let Some(value) = self.values.remove(old) else {
+            return false;
+        };
+
+        #[cfg(feature = "ordering")]
+        {
+            self.order.retain(|input| *input != new);
+            if let Some(input) = self.order.iter_mut().find(|input| *input == old) {
+                *input = new.clone();
+            }
+        }
+        self.values.insert(new, value);
+        true
+    }
+
+    /// Count the cached values falling in each of `num_buckets` buckets.
+    ///
+    /// Values whose bucket is past the end are counted in the last one.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve_all(0..10);
+    /// // Values 0 to 18, in buckets of 10
+    /// let histogram = doubler.histogram(|value| *value as usize / 10, 2);
+    /// assert_eq!(histogram, [5, 5]);
+    /// ```
+    pub fn histogram<F>(&self, bucket_fn: F, num_buckets: usize) -> Vec<usize>
+    where
+        F: Fn(&TOut) -> usize,
+    {
+        let mut buckets = vec![0; num_buckets];
+        let Some(last) = num_buckets.checked_sub(1) else {
+            return buckets;
+        };
+        for value in self.values.values() {
+            buckets[bucket_fn(value).min(last)] += 1;
+        }
+        buckets
+    }
+
+    /// Get one entry for each distinct cached value.
+    ///
+    /// Which of the inputs sharing a value represents it is unspecified.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut parity = Memory::new(|x: &u32| x % 2);
+    /// parity.resolve_all([1, 2, 3, 4]);
+    /// assert_eq!(parity.dedup().len(), 2);
+    /// ```
+    pub fn dedup(&self) -> Vec<(&TIn, &TOut)>
+    where
+        TOut: Eq + Hash,
+    {
+        let mut seen = HashSet::new();
+        self.values
+            .iter()
+            .filter(|(_, value)| seen.insert(*value))
+            .collect()
+    }
+
+    /// Wrap the memory in an `Arc<Mutex<_>>`, to share it between threads.
+    ///
+    /// Clone the `Arc` to hand out more handles to the same memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    ///
+    /// fn clone_arc<T>(arc: &Arc<Mutex<T>>) -> Arc<Mutex<T>> {
+    ///     Arc::clone(arc)
+    /// }
+    ///
+    /// let doubler = Memory::new(|x: &u32| x * 2).into_arc();
+    /// let handle = clone_arc(&doubler);
+    /// thread::spawn(move || handle.lock().unwrap().resolve(&2))
+    ///     .join()
+    ///     .unwrap();
+    /// assert_eq!(doubler.lock().unwrap().iter().count(), 1);
+    /// ```
+    pub fn into_arc(self) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Get a read-only view of the cached entries, to share between readers.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let view = doubler.observe();
+    /// let other = view;
+    /// assert_eq!(view.get(&2), other.get(&2));
+    /// ```
+    pub const fn observe(&self) -> MemoryView<'_, TIn, TOut> {
+        MemoryView::new(&self.values)
+    }
+
+    /// How long the resolver has been running, across every call.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// use std::time::Duration;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.total_resolver_time(), Duration::ZERO);
+    ///
+    /// doubler.resolve(&2);
+    /// doubler.resolve(&2);
+    /// assert!(doubler.total_resolver_time() < Duration::from_secs(1));
+    /// ```
+    #[cfg(feature = "stats")]
+    pub const fn total_resolver_time(&self) -> Duration {
+        self.stats.resolver_time
+    }
+
+    /// How long a resolver call took on average, or `None` before the first one.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.avg_resolver_time(), None);
+    ///
+    /// doubler.resolve(&2);
+    /// assert!(doubler.avg_resolver_time().is_some());
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn avg_resolver_time(&self) -> Option<Duration> {
+        let calls = u128::from(self.stats.resolver_calls);
+        (calls > 0).then(|| {
+            let nanos = self.stats.resolver_time.as_nanos() / calls;
+            Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+        })
+    }
+
+    /// Store a value, keeping track of when its input was first stored.
+    pub(crate) fn store(&mut self, input: TIn, value: TOut)
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        #[cfg(feature = "ordering")]
+        self.record_order(&input);
+        self.values.insert(input, value);
+    }
+
+    /// Append the input to the insertion order, unless it's already cached.
+    #[cfg(feature = "ordering")]
+    fn record_order(&mut self, input: &TIn)
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        if !self.values.contains_key(input) {
+            self.order.push(input.clone());
+        }
+    }
+
+    /// Remove every cached entry.
+    pub(crate) fn clear_entries(&mut self) {
+        self.values.clear();
+        #[cfg(feature = "ordering")]
+        self.order.clear();
+        #[cfg(feature = "stats")]
+        {
+            self.stats = Stats::new();
+        }
+    }
+
+    /// Forget the order of inputs that are no longer cached.
+    #[cfg(feature = "ordering")]
+    fn prune_order(&mut self)
+    where
+        TIn: Eq + Hash,
+    {
+        let values = &self.values;
+        self.order.retain(|input| values.contains_key(input));
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, R, TIn, TOut> Memory<&'a R, TIn, TOut>
+where
+    R: Fn(&TIn) -> TOut + Sync,
+{
+    /// Create a memory with every given key already resolved,
+    /// computing the entries in parallel on the Rayon thread pool.
+    ///
+    /// The resolver has to be `Fn + Sync` to be shared by the threads,
+    /// it's borrowed for the lifetime of the memory to handle later misses.
+    /// Entries are stored in the order of the keys.
+    ///
+    /// # Panics
+    /// Resumes the panic of a resolver that panicked on one of the threads.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::{CacheStatus, Memory};
+    /// let square = |x: &u64| x * x;
+    /// let mut squares = Memory::prewarm_parallel(&square, 0..1000);
+    /// assert_eq!(squares.probe(&999), CacheStatus::Hit);
+    /// assert_eq!(squares.resolve(&999), 998_001);
+    /// ```
+    pub fn prewarm_parallel<I>(remember: &'a R, keys: I) -> Self
+    where
+        I: IntoParallelIterator<Item = TIn>,
+        TIn: Clone + Eq + Hash + Send,
+        TOut: Send,
+    {
+        let entries: Vec<_> = keys
+            .into_par_iter()
+            .map(|key| {
+                let start = Instant::now();
+                let value = remember(&key);
+                (key, value, start.elapsed())
+            })
+            .collect();
+
+        let mut memory = Self::new(remember);
+        for (key, value, elapsed) in entries {
+            #[cfg(feature = "stats")]
+            memory.record_call(&key, elapsed);
+            #[cfg(not(feature = "stats"))]
+            let _ = elapsed;
+            memory.store(key, value);
+        }
+        memory
+    }
+}
+
+impl<TIn, TOut> FromIterator<(TIn, TOut)> for Memory<fn(&TIn) -> TOut, TIn, TOut>
+where
+    TIn: Clone + Eq + Hash,
+{
+    /// Build a read-only lookup table from pre-computed entries.
+    ///
+    /// The memory has no resolver, resolving an input that is not in the table panics.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut table: Memory<_, _, _> = [(1, "one"), (2, "two")].into_iter().collect();
+    /// assert_eq!(table.resolve(&1), "one");
+    /// ```
+    fn from_iter<I: IntoIterator<Item = (TIn, TOut)>>(iter: I) -> Self {
+        fn missing<TIn, TOut>(_: &TIn) -> TOut {
+            panic!("input is missing from the lookup table")
+        }
+
+        let mut table = Self::new(missing as fn(&TIn) -> TOut);
+        for (key, value) in iter {
+            table.store(key, value);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn it_works_with_primatives() {
+        let mut doubler = Memory::new(|key: &u32| key * 2);
+        assert_eq!(doubler.resolve(&2), 4);
+    }
+
+    #[test]
+    fn it_works_with_strings() {
+        let mut doubler = Memory::new(|key: &String| key.clone() + "test");
+        let test_input = String::from("test");
+        assert_eq!(doubler.resolve(&test_input), "testtest");
+    }
+
+    #[test]
+    fn it_only_runs_once() {
+        let mut counter = 0;
+        let tool = |key: &u32| {
+            counter += 1;
+            key * 2
+        };
+
+        let mut doubler = Memory::new(tool);
+        assert_eq!(doubler.resolve(&2), 4); // counter == 1
+        assert_eq!(doubler.resolve(&2), 4); // counter == 1
+        assert_eq!(doubler.resolve(&3), 6); // counter == 2
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    fn it_works_with_structs() {
+        #[derive(Hash, PartialEq, Eq, Clone)]
+        struct User {
+            scores: Vec<u32>,
+        }
+        impl User {
+            fn total_score(&self) -> u32 {
+                self.scores.iter().sum()
+            }
+        }
+
+        let mut trigger = 0;
+        let tool = |user: &User| -> u32 {
+            trigger += 1;
+            user.total_score()
+        };
+        let test_user = User {
+            scores: Vec::from([1, 2, 3]),
+        };
+
+        let mut memory = Memory::new(tool);
+        let user1_score = memory.resolve(&test_user);
+        let user2_score = memory.resolve(&test_user);
+        assert_eq!(user1_score, 6);
+        assert_eq!(user1_score, user2_score);
+        assert_eq!(trigger, 1);
+    }
+
+    #[test]
+    fn it_only_calls_the_fallback_on_a_miss() {
+        let mut resolver_calls = 0;
+        let mut fallback_calls = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            resolver_calls += 1;
+            key * 2
+        });
+
+        let mut fallback = |key: &u32| {
+            fallback_calls += 1;
+            key * 10
+        };
+        assert_eq!(memory.resolve_with_fallback(&2, &mut fallback), 20);
+        assert_eq!(memory.resolve_with_fallback(&2, &mut fallback), 20);
+        assert_eq!(memory.resolve(&2), 20);
+        drop(memory);
+
+        assert_eq!(fallback_calls, 1);
+        assert_eq!(resolver_calls, 0);
+    }
+
+    #[test]
+    fn it_gets_cached_values_or_the_default() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        assert_eq!(memory.get_or_default(&2), 0);
+        assert_eq!(memory.resolve(&2), 4); // The miss was not cached
+        assert_eq!(memory.get_or_default(&2), 4);
+    }
+
+    #[test]
+    fn it_inserts_the_default_on_a_miss() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(*memory.get_or_insert_default(2), 4);
+        assert_eq!(*memory.get_or_insert_default(3), 0);
+        assert_eq!(memory.resolve(&3), 0);
+        drop(memory);
+
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_collects_into_a_lookup_table() {
+        let mut table: Memory<_, _, _> = (1..=3).map(|key: u32| (key, key * 10)).collect();
+        assert_eq!(table.resolve(&1), 10);
+        assert_eq!(table.resolve(&3), 30);
+    }
+
+    #[test]
+    #[should_panic(expected = "input is missing from the lookup table")]
+    fn it_panics_on_a_lookup_table_miss() {
+        let mut table: Memory<_, _, _> = [(1, 10), (3, 30)].into_iter().collect();
+        table.resolve(&2);
+    }
+
+    #[test]
+    fn it_probes_without_side_effects() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+
+        assert_eq!(memory.probe(&2), CacheStatus::Miss);
+        assert_eq!(memory.probe(&2), CacheStatus::Miss);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.probe(&2), CacheStatus::Hit);
+        assert_eq!(memory.probe(&3), CacheStatus::Miss);
+        drop(memory);
+
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_updates_values_in_place() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+        for key in 1..=3 {
+            memory.resolve(&key);
+        }
+
+        for (_, value) in memory.iter_mut() {
+            *value *= 2;
+        }
+        assert_eq!(memory.resolve(&1), 4);
+        assert_eq!(memory.resolve(&2), 8);
+        assert_eq!(memory.resolve(&3), 12);
+        drop(memory);
+
+        assert_eq!(counter, 3);
+    }
+
+    #[test]
+    fn it_remaps_keys() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        for key in 1..=3 {
+            memory.resolve(&key);
+        }
+
+        let remapped = memory.map_keys(|key| format!("key-{key}"));
+        let mut entries: Vec<_> = remapped.iter().collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            [
+                (&String::from("key-1"), &2),
+                (&String::from("key-2"), &4),
+                (&String::from("key-3"), &6),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_partitions_entries() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        for key in 0..20 {
+            memory.resolve(&key);
+        }
+
+        let (hot, cold) = memory.partition(|_, value| *value >= 30);
+        assert_eq!(hot.len(), 5);
+        assert_eq!(cold.len(), 15);
+        assert!(hot.values().all(|value| *value >= 30));
+        assert!(cold.values().all(|value| *value < 30));
+    }
+
+    #[test]
+    fn it_bypasses_the_cache_when_uncached() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 10 + counter
+        });
+
+        assert_eq!(memory.resolve(&2), 21);
+        assert_eq!(memory.resolve_uncached(&2), 22);
+        assert_eq!(memory.resolve_uncached(&3), 33);
+        assert_eq!(memory.probe(&3), CacheStatus::Miss);
+        assert_eq!(memory.resolve(&2), 21);
+        assert_eq!(counter, 3);
+    }
+
+    #[test]
+    fn it_only_inserts_the_value_once() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+
+        assert_eq!(*memory.resolve_or_insert(2, 10), 10);
+        assert_eq!(*memory.resolve_or_insert(2, 20), 10);
+        assert_eq!(memory.resolve(&2), 10);
+        assert_eq!(counter, 0);
+    }
+
+    #[test]
+    fn it_resolves_borrowed_inputs() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &String| {
+            counter += 1;
+            key.clone() + "test"
+        });
+
+        assert_eq!(memory.resolve_ref_borrow("unit"), "unittest");
+        assert_eq!(memory.resolve_ref_borrow("unit"), "unittest");
+        assert_eq!(memory.resolve(&String::from("unit")), "unittest");
+        assert_eq!(memory.resolve_ref_borrow("other"), "othertest");
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    fn it_counts_entries_by_projection() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        for key in 1..=7 {
+            memory.resolve(&key);
+        }
+
+        let counts = memory.count_by(|key| if key % 2 == 0 { "even" } else { "odd" });
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["even"], 3);
+        assert_eq!(counts["odd"], 4);
+    }
+
+    #[test]
+    #[cfg(feature = "size-estimator")]
+    fn it_estimates_the_size_of_values() {
+        let mut memory = Memory::new(|key: &usize| "x".repeat(*key));
+        let mut previous = memory.size_of_values();
+        for key in [10, 1000, 100] {
+            memory.resolve(&key);
+            let current = memory.size_of_values();
+            assert!(current > previous);
+            previous = current;
+        }
+
+        let largest: Vec<_> = memory.largest_values(2);
+        assert_eq!(largest.len(), 2);
+        assert_eq!(*largest[0].0, 1000);
+        assert_eq!(*largest[1].0, 100);
+        assert!(largest[0].1 > largest[1].1);
+    }
+
+    #[test]
+    fn it_finds_entries_by_predicate() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+        for key in 0..10 {
+            memory.resolve(&key);
+        }
+
+        assert_eq!(memory.find(|_, value| *value == 8), Some((&4, &8)));
+        assert_eq!(memory.find(|_, value| *value > 100), None);
+
+        let mut matches = memory.find_all(|_, value| (4..=8).contains(value));
+        matches.sort_unstable();
+        assert_eq!(matches, [(&2, &4), (&3, &6), (&4, &8)]);
+        assert!(memory.find_all(|key, _| *key > 100).is_empty());
+        assert_eq!(counter, 10);
+    }
+
+    #[test]
+    fn it_finds_the_extreme_values() {
+        let mut memory = Memory::new(|key: &i32| (key - 5).abs());
+        for key in 0..=12 {
+            memory.resolve(&key);
+        }
+
+        assert_eq!(memory.max_by_value(), Some((&12, &7)));
+        assert_eq!(memory.min_by_value(), Some((&5, &0)));
+    }
+
+    #[test]
+    fn it_resolves_all_inputs() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+        memory.resolve(&1);
+
+        let values = memory.resolve_all([1, 2, 3, 2]);
+        assert_eq!(values, HashMap::from([(1, 2), (2, 4), (3, 6)]));
+        assert_eq!(memory.probe(&3), CacheStatus::Hit);
+        assert_eq!(counter, 3);
+    }
+
+    #[test]
+    fn it_collects_entries_that_are_no_longer_live() {
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let mut memory = Memory::new(|key: &u64| (key * 2, start + Duration::from_secs(*key)));
+        for key in 1..=5 {
+            memory.resolve(&key);
+        }
+
+        // Pretend three seconds have passed, expiring anything stamped before then
+        let now = start + Duration::from_secs(3);
+        memory.gc(|_, (_, stamped)| *stamped >= now);
+        let mut live: Vec<_> = memory.iter().map(|(key, _)| *key).collect();
+        live.sort_unstable();
+        assert_eq!(live, [3, 4, 5]);
+
+        assert_eq!(memory.probe(&1), CacheStatus::Miss);
+        assert_eq!(memory.resolve(&1).0, 2);
+    }
+
+    #[test]
+    fn it_turns_into_a_closure() {
+        fn apply_twice(mut f: impl FnMut(&u32) -> u32, input: u32) -> u32 {
+            f(&input) + f(&input)
+        }
+
+        let mut counter = 0;
+        let memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+
+        assert_eq!(apply_twice(memory.into_fn(), 3), 12);
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_transforms_the_resolver() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        memory.resolve(&1);
+        memory.resolve(&2);
+
+        let mut counter = 0;
+        let mut memory = memory.transform(|key: &u32| {
+            counter += 1;
+            key * 100
+        });
+        assert_eq!(memory.resolve(&1), 2);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&3), 300);
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_splits_into_a_sub_memory() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        for key in 0..10 {
+            memory.resolve(&key);
+        }
+
+        let mut small = memory.split(|key| *key < 3);
+        let mut split_keys: Vec<_> = small.iter().map(|(key, _)| *key).collect();
+        split_keys.sort_unstable();
+        assert_eq!(split_keys, [0, 1, 2]);
+        assert_eq!(memory.iter().count(), 7);
+        assert!(memory.iter().all(|(key, _)| *key >= 3));
+
+        assert_eq!(memory.probe(&1), CacheStatus::Miss);
+        assert_eq!(small.probe(&5), CacheStatus::Miss);
+        assert_eq!(small.resolve(&5), 10);
+    }
+
+    #[test]
+    fn it_absorbs_a_split_memory() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        for key in 0..10 {
+            memory.resolve(&key);
+        }
+
+        let small = memory.split(|key| *key < 3);
+        memory.absorb(small);
+        let mut keys: Vec<_> = memory.iter().map(|(key, _)| *key).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_prefers_its_own_entries_when_absorbing() {
+        let mut memory = Memory::new(|key: &u32| key * 2);
+        memory.resolve(&1);
+
+        let mut other = memory.split(|_| false);
+        other.resolve_or_insert(1, 100);
+        other.resolve_or_insert(2, 200);
+
+        memory.absorb(other);
+        assert_eq!(memory.resolve(&1), 2);
+        assert_eq!(memory.resolve(&2), 200);
+    }
+
+    #[test]
+    fn it_applies_a_transformation_to_selected_values() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Format {
+            V1(u32),
+            V2(String),
+        }
+
+        let mut memory = Memory::new(|key: &u32| Format::V1(*key));
+        for key in 0..4 {
+            memory.resolve(&key);
+        }
+
+        memory.apply(
+            |key| key % 2 == 0,
+            |_, value| match value {
+                Format::V1(number) => Format::V2(number.to_string()),
+                upgraded @ Format::V2(_) => upgraded,
+            },
+        );
+        assert_eq!(memory.resolve(&0), Format::V2(String::from("0")));
+        assert_eq!(memory.resolve(&1), Format::V1(1));
+        assert_eq!(memory.resolve(&2), Format::V2(String::from("2")));
+        assert_eq!(memory.resolve(&3), Format::V1(3));
+    }
+
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn it_tracks_the_order_inputs_were_first_resolved() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&3);
+        memory.resolve(&1);
+        memory.resolve(&3);
+        memory.resolve_or_insert(2, 0);
+        assert_eq!(memory.access_order(), [&3, &1, &2]);
+
+        memory.gc(|key, _| *key != 1);
+        assert_eq!(memory.access_order(), [&3, &2]);
+
+        memory.resolve(&1);
+        assert_eq!(memory.access_order(), [&3, &2, &1]);
+    }
+
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn it_keeps_the_order_of_both_halves_of_a_split() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        for x in [3, 0, 5, 2, 1, 4] {
+            memory.resolve(&x);
+        }
+
+        let evens = memory.split(|x| x % 2 == 0);
+        assert_eq!(evens.access_order(), [&0, &2, &4]);
+        assert_eq!(evens.first_resolved(), Some((&0, &0)));
+        assert_eq!(memory.access_order(), [&3, &5, &1]);
+    }
+
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn it_lists_the_most_recently_resolved_inputs() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        for x in 0..20 {
+            memory.resolve(&x);
+        }
+        memory.resolve(&3); // Already cached, so not recent
+
+        assert_eq!(memory.recent(5), [15, 16, 17, 18, 19]);
+        assert_eq!(memory.recent(0), [] as [u32; 0]);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn it_ranks_inputs_by_their_misses() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        for round in 0..3 {
+            for x in 0..=round {
+                memory.resolve(&x);
+            }
+            memory.gc(|_, _| false);
+        }
+        memory.resolve(&0);
+
+        assert_eq!(memory.top_n_misses(2), [(&0, 4), (&1, 2)]);
+        assert_eq!(memory.top_n_misses(10).len(), 3);
+    }
+
+    #[cfg(feature = "explain")]
+    #[test]
+    fn it_explains_the_cache() {
+        let mut memory = Memory::new(|x: &u32| Some(x * 2));
+        assert_eq!(memory.explain(), "");
+
+        memory.resolve(&10);
+        memory.resolve(&3);
+        assert_eq!(memory.explain(), "10 → Some(20)\n3 → Some(6)");
+        assert_eq!(memory.explain_key(&3).as_deref(), Some("3 → Some(6)"));
+    }
+
+    #[test]
+    fn it_prefetches_only_as_far_as_the_iterator_is_advanced() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+
+        {
+            let mut prefetch = memory.prefetch_lazy(vec![1, 2, 3, 4, 5]);
+            assert_eq!(prefetch.next(), Some(2));
+            assert_eq!(prefetch.next(), Some(4));
+        }
+        assert_eq!(memory.probe(&2), CacheStatus::Hit);
+        assert_eq!(memory.probe(&3), CacheStatus::Miss);
+
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    fn it_coerces_values_into_a_newtype() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Score(u32);
+
+        impl From<u32> for Score {
+            fn from(value: u32) -> Self {
+                Self(value)
+            }
+        }
+
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+        memory.resolve(&1);
+
+        {
+            let mut scores = memory.coerce::<Score>();
+            assert_eq!(scores.resolve(&1), Score(2));
+            assert_eq!(scores.resolve(&5), Score(10));
+            assert_eq!(scores.resolve(&5), Score(10));
+        }
+
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    fn it_is_send_and_sync_when_its_parts_are() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let memory = Memory::new(|x: &u32| x * 2);
+        assert_send(&memory);
+        assert_sync(&memory);
+
+        let offset = std::sync::Arc::new(1);
+        let shared = Memory::new(move |x: &u32| x + *offset);
+        assert_send(&shared);
+        assert_sync(&shared);
+
+        let cell = std::cell::Cell::new(0);
+        let counting = Memory::new(move |x: &String| {
+            cell.set(cell.get() + 1);
+            x.len()
+        });
+        assert_send(&counting); // A `Cell` can move, but can't be shared
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn it_prewarms_every_key_in_parallel() {
+        let calls = AtomicU32::new(0);
+        let resolver = |x: &u32| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            x * 2
+        };
+
+        let mut memory = Memory::prewarm_parallel(&resolver, 0..100);
+        assert_eq!(calls.load(Ordering::SeqCst), 100);
+
+        for x in 0..100 {
+            assert_eq!(memory.resolve(&x), x * 2);
+        }
+        assert_eq!(memory.resolve(&100), 200);
+        assert_eq!(calls.load(Ordering::SeqCst), 101);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn it_prewarms_nothing_without_keys() {
+        let resolver = |x: &u32| x * 2;
+        let memory = Memory::prewarm_parallel(&resolver, Vec::new());
+        assert_eq!(memory.iter().count(), 0);
+    }
+
+    #[cfg(all(feature = "rayon", feature = "ordering"))]
+    #[test]
+    fn it_prewarms_in_the_order_of_the_keys() {
+        let resolver = |x: &u32| x * 2;
+        let memory = Memory::prewarm_parallel(&resolver, vec![5, 3, 9, 1, 7]);
+        assert_eq!(memory.access_order(), [&5, &3, &9, &1, &7]);
+    }
+
+    #[cfg(all(feature = "rayon", feature = "stats"))]
+    #[test]
+    fn it_counts_prewarmed_resolver_calls() {
+        let resolver = |x: &u32| x * 2;
+        let memory = Memory::prewarm_parallel(&resolver, 0..10);
+        assert_eq!(memory.stats.resolver_calls, 10);
+        assert_eq!(memory.stats.misses.get(&3), Some(&1));
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn it_averages_past_u32_resolver_calls() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.stats.resolver_calls = 1 << 33;
+        memory.stats.resolver_time = Duration::from_secs(1 << 33);
+        memory.resolve(&1);
+
+        assert_eq!(memory.stats.resolver_calls, (1 << 33) + 1);
+        assert!(memory.avg_resolver_time().unwrap() <= Duration::from_secs(1));
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn it_adds_up_the_time_spent_resolving() {
+        let mut memory = Memory::new(|x: &u32| {
+            std::thread::sleep(Duration::from_millis(10));
+            x * 2
+        });
+        for x in 0..3 {
+            memory.resolve(&x);
+            memory.resolve(&x);
+        }
+
+        assert!(memory.total_resolver_time() >= Duration::from_millis(30));
+        assert!(memory.avg_resolver_time().unwrap() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn it_replays_every_cached_input() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 100 + counter
+        });
+        for x in 1..=3 {
+            memory.resolve(&x);
+        }
+        let before: HashMap<u32, u32> = memory.iter().map(|(k, v)| (*k, *v)).collect();
+
+        memory.replay();
+        for (key, value) in memory.iter() {
+            assert!(*value > before[key]);
+        }
+        assert_eq!(memory.iter().count(), 3);
+
+        assert_eq!(counter, 6);
+    }
+
+    #[test]
+    fn it_resolves_a_sequence_in_order() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+
+        assert_eq!(
+            memory.resolve_sequence(&[5, 1, 5, 3, 1, 5]),
+            [10, 2, 10, 6, 2, 10]
+        );
+        assert_eq!(memory.resolve_sequence(&[]), []);
+
+        assert_eq!(counter, 3);
+    }
+
+    #[test]
+    fn it_reduces_the_cached_values() {
+        let mut memory = Memory::new(|x: &u32| x * 10);
+        assert_eq!(memory.reduce(0, |total, (_, value)| total + value), 0);
+
+        memory.resolve_all(1..=4);
+        assert_eq!(memory.reduce(0, |total, (_, value)| total + value), 100);
+    }
+
+    #[test]
+    fn it_reduces_cached_strings() {
+        let mut memory = Memory::new(|x: &char| x.to_uppercase().to_string());
+        memory.resolve_all(['c', 'a', 'b']);
+
+        let joined = memory.reduce(String::new(), |mut joined, (_, value)| {
+            joined.push_str(value);
+            joined
+        });
+        let mut letters: Vec<char> = joined.chars().collect();
+        letters.sort_unstable();
+        assert_eq!(letters, ['A', 'B', 'C']);
+    }
+
+    #[test]
+    fn it_sorts_the_entries_it_gives_back() {
+        let sorted = || {
+            let mut memory = Memory::new(|x: &i32| x * x);
+            memory.resolve_all([3, -2, 4, 1, -5]);
+            memory.into_sorted_vec(|input, value| (*value, *input))
+        };
+
+        let first = sorted();
+        assert_eq!(first, [(1, 1), (-2, 4), (3, 9), (4, 16), (-5, 25)]);
+        assert_eq!(sorted(), first);
+    }
+
+    #[test]
+    fn it_finds_cached_values_and_their_keys() {
+        let mut memory = Memory::new(|x: &i32| x.abs());
+        memory.resolve_all([-3, 3, 2]);
+
+        assert!(memory.contains_value(&3));
+        assert!(memory.contains_value(&2));
+        assert!(!memory.contains_value(&-3));
+
+        let mut threes = memory.keys_for_value(&3);
+        threes.sort_unstable();
+        assert_eq!(threes, [&-3, &3]);
+        assert_eq!(memory.keys_for_value(&2), [&2]);
+        assert!(memory.keys_for_value(&5).is_empty());
+    }
+
+    #[test]
+    fn it_groups_entries_by_their_class() {
+        let mut memory = Memory::new(|x: &u32| x * 3);
+        memory.resolve_all(0..10);
+
+        let groups = memory.group_by(|_, value| value % 2 == 0);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&true].len(), 5);
+        assert_eq!(groups[&false].len(), 5);
+        assert!(groups[&true].iter().all(|(_, value)| *value % 2 == 0));
+        assert!(groups[&false].iter().all(|(_, value)| *value % 2 == 1));
+    }
+
+    #[test]
+    fn it_counts_inputs_sharing_a_value() {
+        let mut memory = Memory::new(|word: &&str| word.len());
+        memory.resolve_all(["a", "to", "be", "or", "not", "cat"]);
+
+        let counts = memory.value_counts();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&1], 1);
+        assert_eq!(counts[&2], 3);
+        assert_eq!(counts[&3], 2);
+    }
+
+    #[test]
+    fn it_does_not_recompute_defaulted_inputs() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+        memory.resolve(&3);
+        memory.apply_default(1..=3);
+
+        assert_eq!(memory.resolve(&1), 0);
+        assert_eq!(memory.resolve(&2), 0);
+        assert_eq!(memory.resolve(&3), 6);
+        assert_eq!(memory.resolve(&4), 8);
+
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    fn it_exchanges_values_that_match() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+
+        assert_eq!(memory.compare_exchange(&1, &2, 10), Ok(2));
+        assert_eq!(memory.compare_exchange(&1, &10, 20), Ok(10));
+        assert_eq!(memory.resolve(&1), 20);
+    }
+
+    #[test]
+    fn it_keeps_values_that_do_not_match() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+
+        assert_eq!(memory.compare_exchange(&1, &3, 10), Err(2));
+        assert_eq!(memory.resolve(&1), 2);
+    }
+
+    #[test]
+    fn it_does_not_exchange_missing_inputs() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+
+        assert_eq!(memory.compare_exchange(&1, &2, 10), Err(10));
+        assert_eq!(memory.probe(&1), CacheStatus::Miss);
+        assert_eq!(memory.resolve(&1), 2);
+
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_swaps_cached_values() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+
+        assert_eq!(memory.swap(&1, 10), Some(2));
+        assert_eq!(memory.swap(&1, 20), Some(10));
+        assert_eq!(memory.resolve(&1), 20);
+    }
+
+    #[test]
+    fn it_does_not_swap_missing_inputs() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+
+        assert_eq!(memory.swap(&1, 10), None);
+        assert_eq!(memory.probe(&1), CacheStatus::Miss);
+    }
+
+    #[test]
+    fn it_mutates_two_values_at_once() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve_all([1, 2]);
+
+        let (Some(a), Some(b)) = memory.get_two_mut(&1, &2) else {
+            panic!("both inputs are cached");
+        };
+        *a += 10;
+        *b += 20;
+
+        assert_eq!(memory.resolve(&1), 12);
+        assert_eq!(memory.resolve(&2), 24);
+    }
+
+    #[test]
+    fn it_gives_back_missing_and_repeated_inputs_once() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+
+        assert_eq!(memory.get_two_mut(&1, &3), (Some(&mut 2), None));
+        assert_eq!(memory.get_two_mut(&3, &1), (None, Some(&mut 2)));
+        assert_eq!(memory.get_two_mut(&1, &1), (Some(&mut 2), None));
+    }
+
+    #[test]
+    fn it_renames_cached_inputs() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+        memory.resolve_all([1, 2]);
+
+        assert!(memory.rename_key(&1, 2));
+        assert!(!memory.rename_key(&3, 4));
+
+        assert_eq!(memory.probe(&1), CacheStatus::Miss);
+        assert_eq!(memory.probe(&4), CacheStatus::Miss);
+        assert_eq!(memory.resolve(&2), 2);
+
+        assert_eq!(counter, 2);
+    }
+
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn it_keeps_the_order_of_renamed_inputs() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve_all([1, 2, 3]);
+
+        assert!(memory.rename_key(&2, 2));
+        assert_eq!(memory.access_order(), [&1, &2, &3]);
+
+        assert!(memory.rename_key(&1, 5));
+        assert_eq!(memory.access_order(), [&5, &2, &3]);
+        assert_eq!(memory.first_resolved(), Some((&5, &2)));
+
+        // Overwriting a cached input leaves a single position
+        assert!(memory.rename_key(&3, 2));
+        assert_eq!(memory.access_order(), [&5, &2]);
+        assert_eq!(memory.resolve(&2), 6);
+    }
+
+    #[test]
+    fn it_keeps_one_entry_per_value() {
+        let mut memory = Memory::new(|x: &i32| x.abs());
+        memory.resolve_all([-2, -1, 0, 1, 2]);
+
+        let mut unique: Vec<_> = memory
+            .dedup()
+            .into_iter()
+            .map(|(_, value)| *value)
+            .collect();
+        unique.sort_unstable();
+        assert_eq!(unique, [0, 1, 2]);
+        assert!(unique.len() < memory.iter().count());
+    }
+
+    #[test]
+    fn it_shares_the_memory_between_threads() {
+        let calls = AtomicU32::new(0);
+        let memory = Memory::new(|x: &u32| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            x * 2
+        })
+        .into_arc();
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                let memory = Arc::clone(&memory);
+                scope.spawn(move || {
+                    for x in 0..10 {
+                        assert_eq!(memory.lock().unwrap().resolve(&x), x * 2);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn it_runs_the_wrapped_resolver_on_later_misses() {
+        let mut inner_calls = 0;
+        let mut outer_calls = 0;
+        {
+            let mut memory = Memory::new(|x: &u32| {
+                inner_calls += 1;
+                x * 2
+            });
+            memory.resolve(&1);
+
+            let outer_calls = &mut outer_calls;
+            let mut memory = memory.map_resolver(|mut resolve| {
+                move |x: &u32| {
+                    *outer_calls += 1;
+                    resolve(x) + 1
+                }
+            });
+            assert_eq!(memory.resolve(&1), 2);
+            assert_eq!(memory.resolve(&2), 5);
+            assert_eq!(memory.resolve(&2), 5);
+        }
+
+        assert_eq!(inner_calls, 2);
+        assert_eq!(outer_calls, 1);
+    }
+
+    #[test]
+    fn it_warms_the_given_share_of_keys() {
+        let mut counter = 0;
+        {
+            let mut memory = Memory::with_warm_ratio(
+                |x: &u32| {
+                    counter += 1;
+                    x * 2
+                },
+                0..1000,
+                0.3,
+            );
+
+            let warm: Vec<_> = memory.iter().map(|(key, _)| *key).collect();
+            assert_eq!(warm.len(), 300);
+            assert!(warm.iter().all(|key| *key < 1000));
+            // Random keys, not just the first ones
+            assert!(warm.iter().any(|key| *key >= 300));
+
+            for key in 0..1000 {
+                assert_eq!(memory.resolve(&key), key * 2);
+            }
+        }
+
+        assert_eq!(counter, 1000);
+    }
+
+    #[test]
+    fn it_warms_all_or_nothing_at_the_extremes() {
+        let cold = Memory::with_warm_ratio(|x: &u32| x * 2, 0..10, 0.0);
+        let hot = Memory::with_warm_ratio(|x: &u32| x * 2, 0..10, 1.0);
+
+        assert_eq!(cold.iter().count(), 0);
+        assert_eq!(hot.iter().count(), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "warm ratio")]
+    fn it_rejects_ratios_above_one() {
+        Memory::with_warm_ratio(|x: &u32| x * 2, 0..10, 1.5);
+    }
+
+    #[test]
+    fn it_counts_values_per_bucket() {
+        let mut memory = Memory::new(|x: &usize| x * x);
+        memory.resolve_all(0..10);
+
+        // 0 1 4 9 | 16 25 36 49 | 64 81
+        let histogram = memory.histogram(
+            |value| match value {
+                0..=9 => 0,
+                10..=49 => 1,
+                _ => 2,
+            },
+            3,
+        );
+        assert_eq!(histogram, [4, 4, 2]);
+    }
+
+    #[test]
+    fn it_counts_overflowing_values_in_the_last_bucket() {
+        let mut memory = Memory::new(|x: &usize| *x);
+        memory.resolve_all(0..10);
+
+        assert_eq!(memory.histogram(|value| *value, 4), [1, 1, 1, 7]);
+        assert!(memory.histogram(|value| *value, 0).is_empty());
+    }
+
+    #[test]
+    fn it_resolves_owned_and_borrowed_keys_alike() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &String| {
+            counter += 1;
+            x.len()
+        });
+
+        assert_eq!(*memory.resolve_key("unit"), 4);
+        assert_eq!(*memory.resolve_key(&String::from("unit")), 4);
+        assert_eq!(memory.resolve(&String::from("unit")), 4);
+        assert_eq!(*memory.resolve_key(&String::from("souvenir")), 8);
+        assert_eq!(*memory.resolve_key("souvenir"), 8);
+        assert_eq!(memory.iter().count(), 2);
+        drop(memory);
+
+        assert_eq!(counter, 2);
+    }
+
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn it_finds_the_first_and_last_resolved_entries() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        assert_eq!(memory.first_resolved(), None);
+        assert_eq!(memory.last_resolved(), None);
+
+        memory.resolve(&5);
+        assert_eq!(memory.first_resolved(), Some((&5, &10)));
+        assert_eq!(memory.last_resolved(), Some((&5, &10)));
+
+        for x in [2, 8, 5, 2] {
+            memory.resolve(&x);
+        }
+        assert_eq!(memory.first_resolved(), Some((&5, &10)));
+        assert_eq!(memory.last_resolved(), Some((&8, &16)));
+
+        memory.gc(|input, _| *input != 5);
+        assert_eq!(memory.first_resolved(), Some((&2, &4)));
+    }
+
+    #[test]
+    fn it_resolves_within_the_timeout() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        assert_eq!(
+            memory.resolve_with_timeout(&2, Duration::from_secs(5)),
+            Ok(4)
+        );
+        assert_eq!(memory.observe().get(&2), Some(&4));
+    }
+
+    #[test]
+    fn it_gives_up_on_slow_resolvers() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counted = Arc::clone(&calls);
+        let mut memory = Memory::new(move |x: &u32| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            if *x == 1 {
+                thread::sleep(Duration::from_secs(1));
+            }
+            x * 2
+        });
+
+        let timeout = Duration::from_millis(20);
+        assert_eq!(
+            memory.resolve_with_timeout(&1, timeout),
+            Err(TimeoutError { timeout })
+        );
+        assert_eq!(memory.observe().get(&1), None);
+
+        // Plenty of time to spawn the thread, even on a loaded machine
+        let generous = Duration::from_secs(5);
+        assert_eq!(memory.resolve_with_timeout(&2, generous), Ok(4));
+        assert_eq!(memory.resolve_with_timeout(&2, timeout), Ok(4)); // A hit never waits
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
     }
 }
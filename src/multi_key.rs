@@ -0,0 +1,113 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// A memory whose resolver computes a whole batch of inputs in one call.
+///
+/// Fits SQL-style `IN` queries: every miss of a lookup is passed to the resolver at once.
+pub struct MultiKeyMemory<T, TIn, TOut> {
+    remember: T,
+    values: HashMap<TIn, TOut>,
+}
+
+impl<T, TIn, TOut> MultiKeyMemory<T, TIn, TOut>
+where
+    T: FnMut(&[&TIn]) -> HashMap<TIn, TOut>,
+{
+    /// Create a new `MultiKeyMemory`, providing a function resolving a batch of misses
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::MultiKeyMemory;
+    /// use std::collections::HashMap;
+    /// let mut doubler = MultiKeyMemory::new(|inputs: &[&u32]| {
+    ///     inputs.iter().map(|x| (**x, *x * 2)).collect::<HashMap<_, _>>()
+    /// });
+    /// assert_eq!(doubler.resolve(&[&1, &2])[&2], 4);
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self {
+            remember,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Look up every input, resolving all of the misses in a single call.
+    ///
+    /// The resolver isn't called when every input is a hit.
+    /// Values it returns for inputs nobody asked for are ignored,
+    /// and inputs it has no value for are left out of the result.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::MultiKeyMemory;
+    /// use std::collections::HashMap;
+    /// let mut doubler = MultiKeyMemory::new(|inputs: &[&u32]| {
+    ///     inputs.iter().map(|x| (**x, *x * 2)).collect::<HashMap<_, _>>()
+    /// });
+    /// doubler.resolve(&[&1]);
+    ///
+    /// let values = doubler.resolve(&[&1, &2, &3]); // Only resolves 2 and 3
+    /// assert_eq!(values[&1], 2);
+    /// assert_eq!(values[&3], 6);
+    /// ```
+    pub fn resolve<'a>(&mut self, inputs: &[&'a TIn]) -> HashMap<&'a TIn, TOut>
+    where
+        TIn: Eq + Hash,
+        TOut: Clone,
+    {
+        let mut requested: HashSet<&TIn> = HashSet::new();
+        let misses: Vec<&TIn> = inputs
+            .iter()
+            .copied()
+            .filter(|input| !self.values.contains_key(*input) && requested.insert(*input))
+            .collect();
+
+        if !misses.is_empty() {
+            for (input, value) in (self.remember)(&misses) {
+                if requested.contains(&input) {
+                    self.values.insert(input, value);
+                }
+            }
+        }
+
+        inputs
+            .iter()
+            .filter_map(|input| self.values.get(*input).map(|value| (*input, value.clone())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_only_resolves_the_missing_inputs() {
+        let mut batches = Vec::new();
+        let mut memory = MultiKeyMemory::new(|inputs: &[&u32]| {
+            let mut batch: Vec<u32> = inputs.iter().map(|x| **x).collect();
+            batch.sort_unstable();
+            batches.push(batch);
+            inputs.iter().map(|x| (**x, *x * 2)).collect()
+        });
+
+        memory.resolve(&[&1, &2]);
+        let values = memory.resolve(&[&2, &3, &1, &4, &3]);
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[&4], 8);
+        memory.resolve(&[&1, &4]);
+
+        assert_eq!(batches, [vec![1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn it_ignores_unrequested_results() {
+        let mut memory = MultiKeyMemory::new(|_: &[&u32]| HashMap::from([(1, 2), (5, 10)]));
+
+        let values = memory.resolve(&[&1, &2]);
+        assert_eq!(values, HashMap::from([(&1, 2)]));
+        assert!(!memory.values.contains_key(&5));
+    }
+}
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A memory whose keys are scoped under a namespace.
+///
+/// Useful when several logical caches share the same storage
+/// and are keyed on the same type.
+pub struct NamespacedMemory<T, TIn, TOut> {
+    remember: T,
+    namespace: String,
+    values: HashMap<String, HashMap<TIn, TOut>>,
+}
+
+impl<T, TIn, TOut> NamespacedMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    pub(crate) const fn new(
+        remember: T,
+        namespace: String,
+        values: HashMap<String, HashMap<TIn, TOut>>,
+    ) -> Self {
+        Self {
+            remember,
+            namespace,
+            values,
+        }
+    }
+
+    /// Move the memory to another namespace, keeping the stored entries.
+    ///
+    /// Entries resolved under the previous namespace stay in storage,
+    /// but are only visible again after switching back to it.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut memory = Memory::new(|x: &u32| x * 2).namespace("left");
+    /// assert_eq!(memory.resolve(&2), 4);
+    ///
+    /// let mut memory = memory.namespace("right");
+    /// assert_eq!(memory.resolve(&2), 4); // Recalculated under "right"
+    /// ```
+    #[must_use]
+    pub fn namespace(self, ns: impl Into<String>) -> Self {
+        Self {
+            namespace: ns.into(),
+            ..self
+        }
+    }
+
+    /// Search the current namespace for the previous result of this input
+    ///
+    /// On a miss, the result of the resolver is stored under the current namespace.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).namespace("doubler");
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        let entries = self.values.get(&self.namespace);
+        if let Some(value) = entries.and_then(|entries| entries.get(input)) {
+            return value.clone();
+        }
+
+        let result = (self.remember)(input);
+        if let Some(entries) = self.values.get_mut(&self.namespace) {
+            entries.insert(input.clone(), result.clone());
+        } else {
+            let entries = HashMap::from([(input.clone(), result.clone())]);
+            self.values.insert(self.namespace.clone(), entries);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Memory;
+
+    #[test]
+    fn it_does_not_share_entries_across_namespaces() {
+        let mut counter = 0;
+        let tool = |key: &u32| {
+            counter += 1;
+            key * 2
+        };
+
+        let mut memory = Memory::new(tool).namespace("a");
+        assert_eq!(memory.resolve(&2), 4); // counter == 1
+
+        let mut memory = memory.namespace("b");
+        assert_eq!(memory.resolve(&2), 4); // counter == 2
+
+        let mut memory = memory.namespace("a");
+        assert_eq!(memory.resolve(&2), 4); // counter == 2
+        drop(memory);
+
+        assert_eq!(counter, 2);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::recall::Recall;
+
+/// A [`Recall`] letting a read-only observer see the value on every read.
+///
+/// Created with [`Recall::with_observer`].
+pub struct ObservedRecall<T, F, TOut> {
+    recall: Recall<T, TOut>,
+    observer: F,
+}
+
+impl<T, F, TOut> ObservedRecall<T, F, TOut>
+where
+    T: FnMut() -> TOut,
+    F: Fn(&TOut),
+    TOut: Clone,
+{
+    pub(crate) const fn new(recall: Recall<T, TOut>, observer: F) -> Self {
+        Self { recall, observer }
+    }
+
+    /// Resolve the lazy-evaluated function, show it to the observer and return the value.
+    ///
+    /// The evaluation only happens once, but the observer sees every read.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::cell::Cell;
+    /// use souvenir::Recall;
+    /// let reads = Cell::new(0);
+    /// let mut answer = Recall::new(|| 42).with_observer(|_| reads.set(reads.get() + 1));
+    /// assert_eq!(answer.value(), 42);
+    /// assert_eq!(answer.value(), 42);
+    /// assert_eq!(reads.get(), 2);
+    /// ```
+    pub fn value(&mut self) -> TOut {
+        let value = self.recall.value();
+        (self.observer)(&value);
+        value
+    }
+
+    /// Stop observing, getting back the recall along with its value if evaluated.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::cell::Cell;
+    /// use souvenir::Recall;
+    /// let reads = Cell::new(0);
+    /// let mut answer = Recall::new(|| 42).with_observer(|_| reads.set(reads.get() + 1));
+    /// answer.value();
+    ///
+    /// let mut answer = answer.into_recall();
+    /// assert!(answer.is_evaluated());
+    /// assert_eq!(answer.value(), 42);
+    /// assert_eq!(reads.get(), 1);
+    /// ```
+    pub fn into_recall(self) -> Recall<T, TOut> {
+        self.recall
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Recall;
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn it_observes_every_read_but_evaluates_once() {
+        let evaluations = Cell::new(0);
+        let observed = RefCell::new(Vec::new());
+        let mut recall = Recall::new(|| {
+            evaluations.set(evaluations.get() + 1);
+            evaluations.get() * 10
+        })
+        .with_observer(|value| observed.borrow_mut().push(*value));
+
+        for _ in 0..3 {
+            assert_eq!(recall.value(), 10);
+        }
+
+        assert_eq!(evaluations.get(), 1);
+        assert_eq!(*observed.borrow(), [10, 10, 10]);
+    }
+
+    #[test]
+    fn it_stops_observing_once_detached() {
+        let reads = Cell::new(0);
+        let mut recall = Recall::new(|| 42).with_observer(|_| reads.set(reads.get() + 1));
+        recall.value();
+
+        let mut recall = recall.into_recall();
+        recall.value();
+        recall.value();
+
+        assert_eq!(reads.get(), 1);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::memory::Memory;
+use std::{any::Any, collections::HashMap, hash::Hash};
+
+/// A memory stored in a [`MemoryPool`], with a boxed resolver.
+pub type PooledMemory<TIn, TOut> = Memory<Box<dyn FnMut(&TIn) -> TOut>, TIn, TOut>;
+
+/// A collection of independent, named memories.
+///
+/// Each memory can have its own input and output types.
+pub struct MemoryPool {
+    memories: HashMap<String, Box<dyn Pooled>>,
+}
+
+trait Pooled {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn clear(&mut self);
+}
+
+impl<TIn: 'static, TOut: 'static> Pooled for PooledMemory<TIn, TOut> {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clear(&mut self) {
+        self.clear_entries();
+    }
+}
+
+impl MemoryPool {
+    /// Create a new, empty `MemoryPool`
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::MemoryPool;
+    /// let pool = MemoryPool::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            memories: HashMap::new(),
+        }
+    }
+
+    /// Get the memory with the given name, creating it with `resolver` if it doesn't exist yet.
+    ///
+    /// The resolver is ignored when the memory already exists.
+    ///
+    /// # Panics
+    /// Panics if a memory with this name exists for different input or output types.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::MemoryPool;
+    /// let mut pool = MemoryPool::new();
+    /// let doubler = pool.get_or_create("doubler", |x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn get_or_create<TIn, TOut, T>(
+        &mut self,
+        name: &str,
+        resolver: T,
+    ) -> &mut PooledMemory<TIn, TOut>
+    where
+        TIn: Eq + Hash + 'static,
+        TOut: 'static,
+        T: FnMut(&TIn) -> TOut + 'static,
+    {
+        self.memories
+            .entry(name.to_owned())
+            .or_insert_with(|| {
+                let resolver: Box<dyn FnMut(&TIn) -> TOut> = Box::new(resolver);
+                Box::new(Memory::new(resolver))
+            })
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap_or_else(|| panic!("memory `{name}` exists with different types"))
+    }
+
+    /// Clear every entry of the memory with the given name.
+    ///
+    /// The memory and its resolver stay in the pool.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::MemoryPool;
+    /// let mut pool = MemoryPool::new();
+    /// pool.get_or_create("doubler", |x: &u32| x * 2).resolve(&2);
+    /// pool.invalidate_all("doubler");
+    /// ```
+    pub fn invalidate_all(&mut self, name: &str) {
+        if let Some(memory) = self.memories.get_mut(name) {
+            memory.clear();
+        }
+    }
+}
+
+impl Default for MemoryPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CacheStatus;
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn it_keeps_named_memories_apart() {
+        let mut pool = MemoryPool::new();
+        let users = pool.get_or_create("users", |id: &u32| format!("user-{id}"));
+        assert_eq!(users.resolve(&1), "user-1");
+
+        let lengths = pool.get_or_create("lengths", |name: &String| name.len());
+        assert_eq!(lengths.resolve(&String::from("souvenir")), 8);
+
+        let users = pool.get_or_create("users", |_: &u32| String::new());
+        assert_eq!(users.probe(&1), CacheStatus::Hit);
+        assert_eq!(users.resolve(&1), "user-1");
+    }
+
+    #[test]
+    fn it_invalidates_a_single_memory() {
+        let counter = Rc::new(Cell::new(0));
+        let mut pool = MemoryPool::new();
+
+        let calls = Rc::clone(&counter);
+        pool.get_or_create("doubler", move |key: &u32| {
+            calls.set(calls.get() + 1);
+            key * 2
+        })
+        .resolve(&2);
+        pool.get_or_create("tripler", |key: &u32| key * 3)
+            .resolve(&2);
+
+        pool.invalidate_all("doubler");
+        let doubler = pool.get_or_create("doubler", |_: &u32| 0_u32);
+        assert_eq!(doubler.resolve(&2), 4);
+        assert_eq!(counter.get(), 2);
+
+        let tripler = pool.get_or_create("tripler", |_: &u32| 0_u32);
+        assert_eq!(tripler.probe(&2), CacheStatus::Hit);
+    }
+
+    #[test]
+    #[should_panic(expected = "memory `users` exists with different types")]
+    fn it_rejects_mismatched_types() {
+        let mut pool = MemoryPool::new();
+        pool.get_or_create("users", |id: &u32| id * 2);
+        pool.get_or_create("users", |id: &u64| id * 2);
+    }
+}
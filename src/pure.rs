@@ -0,0 +1,64 @@
+use crate::memory::Memory;
+use std::hash::Hash;
+
+/// A memory whose resolver is a plain function, rather than a closure.
+///
+/// The resolver captures nothing, so it's `Copy`, `Send` and `Sync`,
+/// and the memory can go wherever its inputs and outputs can.
+pub struct PureMemory<TIn, TOut>(Memory<fn(&TIn) -> TOut, TIn, TOut>);
+
+impl<TIn, TOut> PureMemory<TIn, TOut> {
+    /// Create a new `PureMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::PureMemory;
+    /// fn square(x: &u64) -> u64 {
+    ///     x * x
+    /// }
+    ///
+    /// let mut squares = PureMemory::new(square);
+    /// assert_eq!(squares.resolve(&3), 9);
+    /// ```
+    #[must_use]
+    pub fn new(f: fn(&TIn) -> TOut) -> Self {
+        Self(Memory::new(f))
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::PureMemory;
+    /// let mut doubler = PureMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        self.0.resolve(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_can_be_sent_to_another_thread() {
+        let mut memory = PureMemory::new(|x: &u32| x * 2);
+        memory.resolve(&2);
+
+        let handle = std::thread::spawn(move || {
+            let value = memory.resolve(&3);
+            (memory, value)
+        });
+        let (mut memory, value) = handle.join().unwrap();
+
+        assert_eq!(value, 6);
+        assert_eq!(memory.resolve(&2), 4);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::memory::Memory;
+use std::{
+    error::Error,
+    fmt,
+    hash::Hash,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The error returned when a [`RateLimitedMemory`] may not call its resolver yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+    /// How long until the resolver may be called again.
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limited, retry after {:?}", self.retry_after)
+    }
+}
+
+impl Error for RateLimited {}
+
+/// A memory that calls its resolver no more than a given number of times per second.
+///
+/// Hits are never limited, only the misses reaching the resolver.
+/// Calls are spread evenly, so a burst of misses doesn't go through all at once.
+pub struct RateLimitedMemory<T, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    interval: Duration,
+    next_call: Instant,
+}
+
+impl<T, TIn, TOut> RateLimitedMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Create a new `RateLimitedMemory`, calling the resolver at most `rate` times per second
+    ///
+    /// # Panics
+    /// Panics if `rate` is not a positive number,
+    /// or is so small that the time between calls doesn't fit in a [`Duration`].
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::RateLimitedMemory;
+    /// let mut doubler = RateLimitedMemory::new(|x: &u32| x * 2, 100.0);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T, rate: f64) -> Self {
+        // A zero, negative or NaN rate has no valid interval either
+        let interval = Duration::try_from_secs_f64(rate.recip())
+            .ok()
+            .filter(|_| rate.is_finite());
+        let Some(interval) = interval else {
+            panic!("rate must be a positive number of calls per second, got {rate}");
+        };
+        Self {
+            memory: Memory::new(remember),
+            interval,
+            next_call: Instant::now(),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, sleeps until the rate limit allows the resolver to be called.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::RateLimitedMemory;
+    /// let mut doubler = RateLimitedMemory::new(|x: &u32| x * 2, 100.0);
+    /// assert_eq!(doubler.resolve(&1), 2);
+    /// assert_eq!(doubler.resolve(&2), 4); // Waited about 10ms
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        loop {
+            match self.try_resolve(input) {
+                Ok(value) => return value,
+                Err(limited) => thread::sleep(limited.retry_after),
+            }
+        }
+    }
+
+    /// Search the memory for the previous result of this input,
+    /// without waiting on the rate limit.
+    ///
+    /// # Errors
+    /// Returns [`RateLimited`] on a miss when the resolver may not be called yet.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::RateLimitedMemory;
+    /// let mut doubler = RateLimitedMemory::new(|x: &u32| x * 2, 1.0);
+    /// assert_eq!(doubler.try_resolve(&1), Ok(2));
+    /// assert!(doubler.try_resolve(&2).is_err());
+    /// assert_eq!(doubler.try_resolve(&1), Ok(2)); // Hits aren't limited
+    /// ```
+    pub fn try_resolve(&mut self, input: &TIn) -> Result<TOut, RateLimited>
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            return Ok(value.clone());
+        }
+
+        let now = Instant::now();
+        if now < self.next_call {
+            return Err(RateLimited {
+                retry_after: self.next_call - now,
+            });
+        }
+        self.next_call = now + self.interval;
+
+        let result = self.memory.compute(input);
+        self.memory.store(input.clone(), result.clone());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_limits_resolver_calls_under_a_burst() {
+        let mut counter = 0;
+        let mut memory = RateLimitedMemory::new(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            20.0,
+        );
+
+        let start = Instant::now();
+        let mut key = 0;
+        while start.elapsed() < Duration::from_millis(200) {
+            if memory.try_resolve(&key).is_ok() {
+                key += 1;
+            }
+        }
+
+        // One call straight away, then one every 50ms
+        assert!(counter <= 5, "resolver was called {counter} times");
+    }
+
+    #[test]
+    fn it_waits_for_the_rate_limit() {
+        let mut memory = RateLimitedMemory::new(|x: &u32| x * 2, 50.0);
+
+        let start = Instant::now();
+        for key in 0..5 {
+            assert_eq!(memory.resolve(&key), key * 2);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(80));
+
+        let start = Instant::now();
+        for key in 0..5 {
+            assert_eq!(memory.resolve(&key), key * 2);
+        }
+        assert!(start.elapsed() < Duration::from_millis(20));
+    }
+
+    #[test]
+    #[should_panic(expected = "positive number")]
+    fn it_rejects_a_zero_rate() {
+        RateLimitedMemory::new(|x: &u32| x * 2, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "positive number")]
+    fn it_rejects_a_rate_too_small_for_a_duration() {
+        RateLimitedMemory::new(|x: &u32| x * 2, 1e-300);
+    }
+}
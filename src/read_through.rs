@@ -0,0 +1,174 @@
+use crate::memory::Memory;
+use std::hash::Hash;
+
+/// A memory backed by an authoritative data source.
+///
+/// Lookups go through the in-process cache first, then the source,
+/// and only then the resolver of the wrapped memory.
+/// Values computed by the resolver are written back to the source.
+pub struct ReadThroughMemory<T, S, W, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    source: S,
+    writer: W,
+}
+
+/// A memory checking a read-only fallback before its resolver.
+///
+/// Created by [`Memory::with_read_fallback`].
+pub type FallbackMemory<T, F, TIn, TOut> = ReadThroughMemory<T, F, fn(&TIn, &TOut), TIn, TOut>;
+
+impl<T, S, W, TIn, TOut> ReadThroughMemory<T, S, W, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+    S: FnMut(&TIn) -> Option<TOut>,
+    W: FnMut(&TIn, &TOut),
+{
+    pub(crate) const fn new(memory: Memory<T, TIn, TOut>, source: S, writer: W) -> Self {
+        Self {
+            memory,
+            source,
+            writer,
+        }
+    }
+
+    /// Search the in-process cache, then the source, then the resolver.
+    ///
+    /// Whichever level answers, the value is stored in the in-process cache
+    /// so the next lookup for the same input never leaves the process.
+    /// A value computed by the resolver is also written back to the source.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut lookup = Memory::new(|x: &u32| x * 2)
+    ///     .read_through(|x: &u32| if *x > 10 { Some(0) } else { None }, |_: &u32, _: &u32| {});
+    ///
+    /// assert_eq!(lookup.resolve(&2), 4);
+    /// assert_eq!(lookup.resolve(&20), 0);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            return value.clone();
+        }
+
+        let result = if let Some(value) = (self.source)(input) {
+            value
+        } else {
+            let value = self.memory.compute(input);
+            (self.writer)(input, &value);
+            value
+        };
+        self.memory.store(input.clone(), result.clone());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Memory;
+    use std::{cell::RefCell, collections::HashMap};
+
+    #[test]
+    fn it_checks_the_source_before_the_resolver() {
+        let calls = RefCell::new(Vec::new());
+        let mut lookup = Memory::new(|key: &u32| {
+            calls.borrow_mut().push("resolver");
+            key * 2
+        })
+        .read_through(
+            |key: &u32| {
+                calls.borrow_mut().push("source");
+                if *key == 1 {
+                    Some(100)
+                } else {
+                    None
+                }
+            },
+            |_: &u32, _: &u32| calls.borrow_mut().push("write"),
+        );
+
+        assert_eq!(lookup.resolve(&1), 100);
+        assert_eq!(*calls.borrow(), ["source"]);
+
+        assert_eq!(lookup.resolve(&2), 4);
+        assert_eq!(*calls.borrow(), ["source", "source", "resolver", "write"]);
+
+        // Cache hits never reach the source
+        assert_eq!(lookup.resolve(&2), 4);
+        assert_eq!(calls.borrow().len(), 4);
+    }
+
+    #[test]
+    fn it_writes_computed_values_back_to_the_source() {
+        let source = RefCell::new(HashMap::from([(1, 100)]));
+        let mut lookup = Memory::new(|key: &u32| key * 2).read_through(
+            |key: &u32| source.borrow().get(key).copied(),
+            |key: &u32, value: &u32| {
+                source.borrow_mut().insert(*key, *value);
+            },
+        );
+
+        assert_eq!(lookup.resolve(&1), 100);
+        assert_eq!(lookup.resolve(&2), 4);
+        drop(lookup);
+
+        // Only the computed value was written
+        assert_eq!(source.into_inner(), HashMap::from([(1, 100), (2, 4)]));
+    }
+
+    #[test]
+    fn it_caches_values_from_every_level() {
+        let mut source_calls = 0;
+        let mut resolver_calls = 0;
+        let mut lookup = Memory::new(|key: &u32| {
+            resolver_calls += 1;
+            key * 2
+        })
+        .read_through(
+            |key: &u32| {
+                source_calls += 1;
+                if *key == 1 {
+                    Some(100)
+                } else {
+                    None
+                }
+            },
+            |_: &u32, _: &u32| {},
+        );
+
+        assert_eq!(lookup.resolve(&1), 100);
+        assert_eq!(lookup.resolve(&1), 100);
+        assert_eq!(lookup.resolve(&2), 4);
+        assert_eq!(lookup.resolve(&2), 4);
+        drop(lookup);
+
+        assert_eq!(source_calls, 2);
+        assert_eq!(resolver_calls, 1);
+    }
+
+    #[test]
+    fn it_short_circuits_the_resolver_with_a_fallback() {
+        let mut resolver_calls = 0;
+        let table = [(1, 100), (2, 200)];
+        let mut lookup = Memory::new(|key: &u32| {
+            resolver_calls += 1;
+            key * 2
+        })
+        .with_read_fallback(|key: &u32| {
+            table
+                .iter()
+                .find(|(entry, _)| entry == key)
+                .map(|(_, value)| *value)
+        });
+
+        assert_eq!(lookup.resolve(&1), 100);
+        assert_eq!(lookup.resolve(&2), 200);
+        assert_eq!(lookup.resolve(&3), 6);
+        assert_eq!(lookup.resolve(&3), 6);
+        assert_eq!(resolver_calls, 1);
+    }
+}
@@ -1,9 +1,16 @@
+use crate::broadcast::{BroadcastRecall, BroadcastReceiver};
+use crate::chained::ChainedRecall;
+use crate::defaulted::DefaultedRecall;
+use crate::observed::ObservedRecall;
+use crate::then::ThenRecall;
+use std::sync::{Arc, Mutex};
+
 /// A general purpose structure for a one-time lazy evaluation.
 /// Useful when you want to declare the logic now,
 /// but execute it later (and only once!)
 pub struct Recall<T, TOut> {
-    evaluator: T,
-    value: Option<TOut>,
+    pub(crate) evaluator: T,
+    pub(crate) value: Option<TOut>,
 }
 
 impl<T, TOut> Recall<T, TOut>
@@ -44,20 +51,215 @@ where
     /// assert_eq!(some_expensive_calc.value(), 42); // Was instant!
     /// ```
     pub fn value(&mut self) -> TOut {
-        match &self.value {
-            Some(value) => (*value).clone(),
-            None => {
-                let value = (self.evaluator)();
-                self.value = Some(value.clone());
-                value
-            }
+        if let Some(value) = &self.value {
+            value.clone()
+        } else {
+            let value = (self.evaluator)();
+            self.value = Some(value.clone());
+            value
+        }
+    }
+
+    /// Resolve the lazy-evaluated function only if the condition holds.
+    ///
+    /// Returns `None` without evaluating when the condition is false.
+    /// Once evaluated, the value is remembered as with `value`.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut config = Recall::new(|| "heavy config");
+    /// assert_eq!(config.evaluate_if(false), None);
+    /// assert!(!config.is_evaluated());
+    ///
+    /// assert_eq!(config.evaluate_if(true), Some("heavy config"));
+    /// assert!(config.is_evaluated());
+    /// ```
+    pub fn evaluate_if(&mut self, condition: bool) -> Option<TOut> {
+        condition.then(|| self.value())
+    }
+
+    /// Check whether the lazy-evaluated function has already resolved.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut answer = Recall::new(|| 42);
+    /// assert!(!answer.is_evaluated());
+    ///
+    /// answer.value();
+    /// assert!(answer.is_evaluated());
+    /// ```
+    pub const fn is_evaluated(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Forget the evaluated value if the predicate holds for it,
+    /// so the next read evaluates again.
+    ///
+    /// Returns whether the value was forgotten.
+    /// Nothing happens if the function hasn't resolved yet.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut answer = Recall::new(|| 42);
+    /// assert!(!answer.reset_if(|_| true)); // Nothing to reset yet
+    ///
+    /// answer.value();
+    /// assert!(!answer.reset_if(|value| *value > 100));
+    /// assert!(answer.reset_if(|value| *value == 42));
+    /// assert!(!answer.is_evaluated());
+    /// ```
+    pub fn reset_if<F>(&mut self, predicate: F) -> bool
+    where
+        F: Fn(&TOut) -> bool,
+    {
+        let reset = self.value.as_ref().is_some_and(predicate);
+        if reset {
+            self.value = None;
         }
+        reset
+    }
+
+    /// Provide a value to hand out until the lazy-evaluated function has resolved.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut greeting = Recall::new(|| String::from("hello")).with_default(String::from("..."));
+    /// assert_eq!(greeting.value(), "...");
+    ///
+    /// greeting.evaluate();
+    /// assert_eq!(greeting.value(), "hello");
+    /// ```
+    pub const fn with_default(self, default: TOut) -> DefaultedRecall<T, TOut> {
+        DefaultedRecall::new(self, default)
+    }
+
+    /// Share the value with any number of receivers, across threads.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let (master, receiver) = Recall::new(|| 42).broadcast();
+    /// let other = receiver.clone();
+    ///
+    /// assert_eq!(*master.value(), 42);
+    /// assert_eq!(receiver.get(), Some(&42));
+    /// assert_eq!(other.get(), Some(&42));
+    /// ```
+    pub fn broadcast(self) -> (BroadcastRecall<T, TOut>, BroadcastReceiver<TOut>) {
+        let master = BroadcastRecall::new(self);
+        let receiver = master.subscribe();
+        (master, receiver)
+    }
+
+    /// Run a follow-up action with the value every time it's read.
+    ///
+    /// Unlike the evaluation, the follow-up isn't limited to the first read.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut answer = Recall::new(|| 42).then(|value| println!("read {value}"));
+    /// assert_eq!(answer.value(), 42);
+    /// assert_eq!(answer.value(), 42); // Printed again
+    /// ```
+    pub const fn then<F>(self, then: F) -> ThenRecall<T, F, TOut>
+    where
+        F: FnMut(&TOut),
+    {
+        ThenRecall::new(self, then)
+    }
+
+    /// Watch every read of the value, without being able to change anything.
+    ///
+    /// The observer runs on every read, while the evaluation still only runs on the first.
+    /// Unlike the follow-up of [`Recall::then`], the observer can't mutate its captures,
+    /// and can be detached again with [`ObservedRecall::into_recall`].
+    /// Handy for counting accesses.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::cell::Cell;
+    /// use souvenir::Recall;
+    /// let reads = Cell::new(0);
+    /// let mut answer = Recall::new(|| 42).with_observer(|_| reads.set(reads.get() + 1));
+    /// answer.value();
+    /// answer.value();
+    /// assert_eq!(reads.get(), 2);
+    /// ```
+    pub const fn with_observer<F>(self, observer: F) -> ObservedRecall<T, F, TOut>
+    where
+        F: Fn(&TOut),
+    {
+        ObservedRecall::new(self, observer)
+    }
+
+    /// Build a second recall from the value of this one, evaluating it on the first read.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut area = Recall::new(|| 3).chain(|side| Recall::new(move || side * side));
+    /// assert_eq!(area.value(), 9);
+    /// ```
+    pub const fn chain<F, G, U>(self, chain: F) -> ChainedRecall<T, F, G, TOut, U>
+    where
+        F: FnMut(TOut) -> Recall<G, U>,
+        G: FnMut() -> U,
+        U: Clone,
+    {
+        ChainedRecall::new(self, chain)
+    }
+
+    /// Wrap the recall in an `Arc<Mutex<_>>`, to share it between threads.
+    ///
+    /// Whichever thread reads first runs the evaluation, and the others wait on the lock.
+    /// Every read takes the lock though, even once the value is known.
+    /// For many readers, [`Recall::broadcast`] or `std::sync::LazyLock`
+    /// hand out the value without locking after initialization.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let config = Recall::new(|| String::from("loaded")).into_arc();
+    /// let reader = Arc::clone(&config);
+    /// thread::spawn(move || assert_eq!(reader.lock().unwrap().value(), "loaded"))
+    ///     .join()
+    ///     .unwrap();
+    /// assert!(config.lock().unwrap().is_evaluated());
+    /// ```
+    pub fn into_arc(self) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Turn the recall into a plain closure, which evaluates at most once.
+    ///
+    /// Useful to hand lazy evaluation to an API expecting a thunk.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut answer = Recall::new(|| 42).into_fn();
+    /// assert_eq!(answer(), 42);
+    /// ```
+    pub fn into_fn(mut self) -> impl FnMut() -> TOut {
+        move || self.value()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        thread,
+    };
 
     #[test]
     fn it_works_with_primatives() {
@@ -109,4 +311,90 @@ mod tests {
         assert_eq!(user1.id, user3.id);
         assert_eq!(trigger, 1);
     }
+
+    #[test]
+    fn it_skips_evaluation_when_the_condition_fails() {
+        let mut counter = 0;
+        let mut recall = Recall::new(|| {
+            counter += 1;
+            42
+        });
+
+        assert_eq!(recall.evaluate_if(false), None);
+        assert!(!recall.is_evaluated());
+
+        assert_eq!(counter, 0);
+    }
+
+    #[test]
+    fn it_evaluates_when_the_condition_holds() {
+        let mut counter = 0;
+        let mut recall = Recall::new(|| {
+            counter += 1;
+            42
+        });
+
+        assert_eq!(recall.evaluate_if(true), Some(42));
+        assert!(recall.is_evaluated());
+        assert_eq!(recall.value(), 42);
+
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_turns_into_a_closure() {
+        use std::sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        };
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let calls = Arc::clone(&counter);
+        let mut thunk = Recall::new(move || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            42
+        })
+        .into_fn();
+
+        let handle = std::thread::spawn(move || thunk() + thunk());
+        assert_eq!(handle.join().unwrap(), 84);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn it_only_resets_when_the_value_is_stale() {
+        let mut counter = 0;
+        let mut recall = Recall::new(|| {
+            counter += 1;
+            counter * 10
+        });
+
+        assert_eq!(recall.value(), 10);
+        assert!(!recall.reset_if(|value| *value < 5));
+        assert_eq!(recall.value(), 10);
+
+        assert!(recall.reset_if(|value| *value < 15));
+        assert_eq!(recall.value(), 20);
+
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    fn it_evaluates_once_across_threads() {
+        let calls = AtomicU32::new(0);
+        let recall = Recall::new(|| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            42
+        })
+        .into_arc();
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                let recall = Arc::clone(&recall);
+                scope.spawn(move || assert_eq!(recall.lock().unwrap().value(), 42));
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
 }
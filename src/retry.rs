@@ -0,0 +1,111 @@
+use crate::fallible_recall::FallibleRecall;
+use std::{thread, time::Duration};
+
+/// A [`FallibleRecall`] that retries its evaluator before giving up.
+pub struct RetryRecall<T, TOut, E, B> {
+    recall: FallibleRecall<T, TOut, E>,
+    max_attempts: usize,
+    backoff: B,
+}
+
+impl<T, TOut, E, B> RetryRecall<T, TOut, E, B>
+where
+    T: FnMut() -> Result<TOut, E>,
+    TOut: Clone,
+    B: Fn(usize) -> Duration,
+{
+    pub(crate) const fn new(
+        recall: FallibleRecall<T, TOut, E>,
+        max_attempts: usize,
+        backoff: B,
+    ) -> Self {
+        Self {
+            recall,
+            max_attempts,
+            backoff,
+        }
+    }
+
+    /// Resolve the lazy-evaluated function, retrying on failure.
+    ///
+    /// The evaluator runs at most `max_attempts` times per call (and at least once).
+    ///
+    /// # Errors
+    /// Returns the error of the final attempt when every attempt failed.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::FallibleRecall;
+    /// let mut broken = FallibleRecall::new(|| Err::<u32, _>("broken")).retry(3);
+    /// assert_eq!(broken.try_value(), Err("broken"));
+    /// ```
+    pub fn try_value(&mut self) -> Result<TOut, E> {
+        let mut failures = 0;
+        loop {
+            match self.recall.try_value() {
+                Ok(value) => return Ok(value),
+                Err(error) => {
+                    failures += 1;
+                    if failures >= self.max_attempts {
+                        return Err(error);
+                    }
+                }
+            }
+
+            let delay = (self.backoff)(failures);
+            if !delay.is_zero() {
+                thread::sleep(delay);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn flaky(failures: u32, counter: &Cell<u32>) -> impl FnMut() -> Result<u32, u32> + '_ {
+        move || {
+            counter.set(counter.get() + 1);
+            if counter.get() <= failures {
+                Err(counter.get())
+            } else {
+                Ok(42)
+            }
+        }
+    }
+
+    #[test]
+    fn it_retries_until_success() {
+        let counter = Cell::new(0);
+        let mut recall = FallibleRecall::new(flaky(2, &counter)).retry(3);
+
+        assert_eq!(recall.try_value(), Ok(42));
+        assert_eq!(recall.try_value(), Ok(42));
+        assert_eq!(counter.get(), 3);
+    }
+
+    #[test]
+    fn it_returns_the_final_error() {
+        let counter = Cell::new(0);
+        let mut recall = FallibleRecall::new(flaky(5, &counter)).retry(3);
+
+        assert_eq!(recall.try_value(), Err(3));
+        assert_eq!(counter.get(), 3);
+    }
+
+    #[test]
+    fn it_backs_off_between_attempts() {
+        let counter = Cell::new(0);
+        let delays = std::cell::RefCell::new(Vec::new());
+        let mut recall =
+            FallibleRecall::new(flaky(2, &counter)).retry_with_backoff(5, |failures| {
+                delays.borrow_mut().push(failures);
+                Duration::from_millis(1)
+            });
+
+        assert_eq!(recall.try_value(), Ok(42));
+        assert_eq!(*delays.borrow(), [1, 2]);
+    }
+}
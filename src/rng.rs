@@ -0,0 +1,67 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+/// A small, non-cryptographic pseudo-random number generator (xorshift64*).
+///
+/// Seeded from the randomly keyed hasher of the standard library,
+/// which is plenty for sampling decisions.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new() -> Self {
+        let seed = RandomState::new().build_hasher().finish();
+        // The state must never be zero
+        Self(seed | 1)
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A uniformly distributed number in `[0.0, 1.0)`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f64(&mut self) -> f64 {
+        // Keep the 53 bits a f64 can represent exactly
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// A uniformly distributed index in `[0, bound)`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn next_index(&mut self, bound: usize) -> usize {
+        // Scaling the bits onto the range avoids the bias of a modulo,
+        // and the result is below `bound` so it always fits back in a usize
+        ((u128::from(self.next_u64()) * bound as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_stays_within_bounds() {
+        let mut rng = Rng::new();
+        assert!((0..1000)
+            .map(|_| rng.next_f64())
+            .all(|x| (0.0..1.0).contains(&x)));
+    }
+
+    #[test]
+    fn it_is_roughly_uniform() {
+        let mut rng = Rng::new();
+        let below_half = (0..10_000).filter(|_| rng.next_f64() < 0.5).count();
+        assert!((4_500..5_500).contains(&below_half));
+    }
+
+    #[test]
+    fn it_picks_indices_within_bounds() {
+        let mut rng = Rng::new();
+        assert!((0..1000).map(|_| rng.next_index(7)).all(|x| x < 7));
+        assert_eq!(rng.next_index(1), 0);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{memory::Memory, rng::Rng};
+use std::hash::Hash;
+
+/// A memory that only caches a random fraction of the values it computes.
+///
+/// Useful on hot paths where an occasional extra miss is acceptable,
+/// to keep adversarial key distributions from flooding the cache.
+pub struct SamplingMemory<T, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    rate: f64,
+    rng: Rng,
+}
+
+impl<T, TIn, TOut> SamplingMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    pub(crate) fn new(memory: Memory<T, TIn, TOut>, rate: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&rate),
+            "sample rate must be between 0.0 and 1.0, got {rate}"
+        );
+        Self {
+            memory,
+            rate,
+            rng: Rng::new(),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result of the resolver is only stored with a probability of the sample rate.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).with_sample_rate(0.5);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            value.clone()
+        } else {
+            let result = self.memory.compute(input);
+            if self.rng.next_f64() < self.rate {
+                self.memory.store(input.clone(), result.clone());
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Memory;
+
+    #[test]
+    fn it_caches_roughly_the_sample_rate() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        })
+        .with_sample_rate(0.3);
+
+        for key in 0..10_000 {
+            memory.resolve(&key);
+        }
+        for key in 0..10_000 {
+            memory.resolve(&key);
+        }
+
+        // Every key missed once, and ~70% of them missed again
+        let recomputed = counter - 10_000;
+        assert!((6_500..7_500).contains(&recomputed), "{recomputed}");
+    }
+
+    #[test]
+    fn it_respects_the_extreme_rates() {
+        let mut counter = 0;
+        let mut never = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        })
+        .with_sample_rate(0.0);
+        for _ in 0..10 {
+            assert_eq!(never.resolve(&2), 4);
+        }
+        assert_eq!(counter, 10);
+
+        let mut counter = 0;
+        let mut always = Memory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        })
+        .with_sample_rate(1.0);
+        for _ in 0..10 {
+            assert_eq!(always.resolve(&2), 4);
+        }
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "sample rate must be between 0.0 and 1.0")]
+    fn it_rejects_invalid_rates() {
+        let _ = Memory::new(|key: &u32| key * 2).with_sample_rate(1.5);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::memory::Memory;
+use std::hash::Hash;
+
+/// A memory comparing its resolver against a shadow resolver on every miss.
+///
+/// Only the primary result is cached and returned,
+/// the shadow result is only checked against it.
+pub struct ShadowMemory<T, U, TIn, TOut, M = fn(&TIn, &TOut, &TOut)> {
+    memory: Memory<T, TIn, TOut>,
+    shadow: U,
+    on_mismatch: M,
+    mismatches: usize,
+}
+
+const fn ignore_mismatch<TIn, TOut>(_: &TIn, _: &TOut, _: &TOut) {}
+
+impl<T, U, TIn, TOut> ShadowMemory<T, U, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+    U: FnMut(&TIn) -> TOut,
+{
+    pub(crate) fn new(memory: Memory<T, TIn, TOut>, shadow: U) -> Self {
+        Self {
+            memory,
+            shadow,
+            on_mismatch: ignore_mismatch,
+            mismatches: 0,
+        }
+    }
+}
+
+impl<T, U, M, TIn, TOut> ShadowMemory<T, U, TIn, TOut, M>
+where
+    T: FnMut(&TIn) -> TOut,
+    U: FnMut(&TIn) -> TOut,
+    M: FnMut(&TIn, &TOut, &TOut),
+{
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, both resolvers are called and their results compared.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).shadow(|x: &u32| x + x);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.mismatches(), 0);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone + PartialEq,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            return value.clone();
+        }
+
+        let result = self.memory.compute(input);
+        let shadowed = (self.shadow)(input);
+        if result != shadowed {
+            self.mismatches += 1;
+            (self.on_mismatch)(input, &result, &shadowed);
+        }
+        self.memory.store(input.clone(), result.clone());
+        result
+    }
+
+    /// Call the given hook with the input, primary and shadow results on every mismatch.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2)
+    ///     .shadow(|x: &u32| x * 3)
+    ///     .on_mismatch(|input, primary, shadow| {
+    ///         eprintln!("{input}: expected {primary}, shadow gave {shadow}");
+    ///     });
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn on_mismatch<N>(self, on_mismatch: N) -> ShadowMemory<T, U, TIn, TOut, N>
+    where
+        N: FnMut(&TIn, &TOut, &TOut),
+    {
+        ShadowMemory {
+            memory: self.memory,
+            shadow: self.shadow,
+            on_mismatch,
+            mismatches: self.mismatches,
+        }
+    }
+
+    /// How many misses the two resolvers disagreed on.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).shadow(|x: &u32| x * 3);
+    /// doubler.resolve(&0);
+    /// doubler.resolve(&1);
+    /// assert_eq!(doubler.mismatches(), 1);
+    /// ```
+    pub const fn mismatches(&self) -> usize {
+        self.mismatches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reports_a_deliberate_mismatch() {
+        let mut reported = Vec::new();
+        let mut memory = Memory::new(|x: &u32| x * 2)
+            .shadow(|x: &u32| if *x == 3 { 0 } else { x * 2 })
+            .on_mismatch(|input, primary, shadow| reported.push((*input, *primary, *shadow)));
+
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&3), 6);
+        assert_eq!(memory.resolve(&3), 6); // Cached, not compared again
+        assert_eq!(memory.mismatches(), 1);
+
+        assert_eq!(reported, [(3, 6, 0)]);
+    }
+
+    #[test]
+    fn it_only_calls_the_shadow_on_a_miss() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| x * 2).shadow(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+
+        memory.resolve(&2);
+        memory.resolve(&2);
+        assert_eq!(memory.mismatches(), 0);
+
+        assert_eq!(counter, 1);
+    }
+}
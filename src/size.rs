@@ -0,0 +1,95 @@
+use std::mem::size_of;
+
+/// An estimate of the memory used by a value, in bytes.
+///
+/// The estimate includes the value itself and whatever it owns on the heap.
+pub trait MemSize {
+    /// Estimate the number of bytes used by the value.
+    fn mem_size(&self) -> usize;
+}
+
+macro_rules! inline_size {
+    ($($ty:ty),*) => {
+        $(
+            impl MemSize for $ty {
+                fn mem_size(&self) -> usize {
+                    size_of::<Self>()
+                }
+            }
+        )*
+    };
+}
+
+inline_size!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    bool,
+    char,
+    ()
+);
+
+impl MemSize for String {
+    fn mem_size(&self) -> usize {
+        size_of::<Self>() + self.capacity()
+    }
+}
+
+impl<T: MemSize> MemSize for Vec<T> {
+    fn mem_size(&self) -> usize {
+        let unused = (self.capacity() - self.len()) * size_of::<T>();
+        size_of::<Self>() + unused + self.iter().map(MemSize::mem_size).sum::<usize>()
+    }
+}
+
+impl<T: MemSize> MemSize for Option<T> {
+    fn mem_size(&self) -> usize {
+        let inner = self
+            .as_ref()
+            .map_or(0, |value| value.mem_size() - size_of::<T>());
+        size_of::<Self>() + inner
+    }
+}
+
+impl<T: MemSize> MemSize for Box<T> {
+    fn mem_size(&self) -> usize {
+        size_of::<Self>() + (**self).mem_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_sizes_primatives() {
+        assert_eq!(1_u32.mem_size(), 4);
+        assert_eq!(1_u64.mem_size(), 8);
+    }
+
+    #[test]
+    fn it_sizes_heap_allocations() {
+        let text = String::with_capacity(32);
+        assert_eq!(text.mem_size(), size_of::<String>() + 32);
+
+        let numbers: Vec<u32> = Vec::with_capacity(4);
+        assert_eq!(numbers.mem_size(), size_of::<Vec<u32>>() + 16);
+
+        let nested = vec![String::with_capacity(8)];
+        assert_eq!(
+            nested.mem_size(),
+            size_of::<Vec<String>>() + size_of::<String>() + 8
+        );
+    }
+}
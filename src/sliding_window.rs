@@ -0,0 +1,161 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// A memory whose entries expire once they haven't been resolved for a while.
+///
+/// Every resolve, hit or miss, restarts the time to live of the entry,
+/// so an entry only expires after going a full `ttl` without being asked for.
+pub struct SlidingWindowMemory<T, TIn, TOut> {
+    remember: T,
+    ttl: Duration,
+    entries: HashMap<TIn, (TOut, Instant)>,
+    now: fn() -> Instant,
+}
+
+impl<T, TIn, TOut> SlidingWindowMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Create a new `SlidingWindowMemory`, expiring entries unused for longer than `ttl`
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SlidingWindowMemory;
+    /// use std::time::Duration;
+    /// let mut doubler = SlidingWindowMemory::new(|x: &u32| x * 2, Duration::from_secs(60));
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T, ttl: Duration) -> Self {
+        Self::with_clock(remember, ttl, Instant::now)
+    }
+
+    pub(crate) fn with_clock(remember: T, ttl: Duration, now: fn() -> Instant) -> Self {
+        Self {
+            remember,
+            ttl,
+            entries: HashMap::new(),
+            now,
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, or if the entry has expired, the resolver is called again.
+    /// Either way, the entry's time to live restarts.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SlidingWindowMemory;
+    /// use std::time::Duration;
+    /// let mut doubler = SlidingWindowMemory::new(|x: &u32| x * 2, Duration::from_secs(60));
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        let now = (self.now)();
+        if let Some((value, accessed)) = self.entries.get_mut(input) {
+            if now.duration_since(*accessed) < self.ttl {
+                *accessed = now;
+                return value.clone();
+            }
+        }
+
+        let result = (self.remember)(input);
+        self.entries.insert(input.clone(), (result.clone(), now));
+        result
+    }
+
+    /// Remove every expired entry.
+    ///
+    /// Expired entries are never served, this only frees their memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SlidingWindowMemory;
+    /// use std::time::Duration;
+    /// let mut doubler = SlidingWindowMemory::new(|x: &u32| x * 2, Duration::ZERO);
+    /// doubler.resolve(&2);
+    /// doubler.purge_expired();
+    /// assert!(doubler.is_empty());
+    /// ```
+    pub fn purge_expired(&mut self) {
+        let now = (self.now)();
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, (_, accessed)| now.duration_since(*accessed) < ttl);
+    }
+
+    /// Check whether nothing is cached, expired or not.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SlidingWindowMemory;
+    /// use std::time::Duration;
+    /// let doubler = SlidingWindowMemory::new(|x: &u32| x * 2, Duration::from_secs(60));
+    /// assert!(doubler.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_clock::{advance, mock_now};
+
+    #[test]
+    fn it_keeps_entries_accessed_within_the_ttl() {
+        let mut counter = 0;
+        let mut memory = SlidingWindowMemory::with_clock(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            Duration::from_secs(10),
+            mock_now,
+        );
+
+        assert_eq!(memory.resolve(&2), 4);
+        for _ in 0..5 {
+            advance(Duration::from_secs(8));
+            assert_eq!(memory.resolve(&2), 4);
+        }
+
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_expires_entries_left_alone_for_the_ttl() {
+        let mut counter = 0;
+        let mut memory = SlidingWindowMemory::with_clock(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            Duration::from_secs(10),
+            mock_now,
+        );
+
+        memory.resolve(&1);
+        memory.resolve(&2);
+        advance(Duration::from_secs(6));
+        memory.resolve(&2);
+        advance(Duration::from_secs(6));
+
+        memory.purge_expired();
+        assert_eq!(memory.entries.len(), 1);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&1), 2);
+
+        assert_eq!(counter, 3);
+    }
+}
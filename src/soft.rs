@@ -0,0 +1,280 @@
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    mem,
+    sync::{Arc, Mutex, PoisonError},
+    thread::{self, JoinHandle},
+};
+
+/// A memory supporting soft invalidation.
+///
+/// A softly invalidated entry keeps serving its old value,
+/// while a fresh one is computed on a background thread.
+/// Because the resolver runs on other threads, it must be `Fn + Send + Sync`.
+pub struct SoftMemory<T, TIn, TOut> {
+    remember: Arc<T>,
+    // Each value along with the generation of the refresh that stored it
+    values: Arc<Mutex<HashMap<TIn, (TOut, u64)>>>,
+    stale: HashSet<TIn>,
+    refreshing: Vec<JoinHandle<()>>,
+    generation: u64,
+    panicked: Option<Box<dyn Any + Send>>,
+}
+
+impl<T, TIn, TOut> SoftMemory<T, TIn, TOut>
+where
+    T: Fn(&TIn) -> TOut + Send + Sync + 'static,
+{
+    /// Create a new `SoftMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SoftMemory;
+    /// let mut doubler = SoftMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self {
+            remember: Arc::new(remember),
+            values: Arc::new(Mutex::new(HashMap::new())),
+            stale: HashSet::new(),
+            refreshing: Vec::new(),
+            generation: 0,
+            panicked: None,
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the resolver runs on the calling thread and the result is stored.
+    /// On a stale hit, the old value is returned immediately
+    /// and the entry is recomputed on a background thread.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SoftMemory;
+    /// let mut doubler = SoftMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash + Send + 'static,
+        TOut: Clone + Send + 'static,
+    {
+        let cached = self
+            .values
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(input)
+            .map(|(value, _)| value.clone());
+
+        if let Some(value) = cached {
+            if self.stale.remove(input) {
+                self.refresh(input.clone());
+            }
+            value
+        } else {
+            let result = (self.remember)(input);
+            self.values
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(input.clone(), (result.clone(), 0));
+            result
+        }
+    }
+
+    /// Mark the entry for this input as stale, without removing it.
+    ///
+    /// The next `resolve` still returns the old value,
+    /// but triggers a recomputation in the background.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SoftMemory;
+    /// let mut doubler = SoftMemory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// doubler.soft_invalidate(&2);
+    /// assert_eq!(doubler.resolve(&2), 4); // Served the stale value
+    /// ```
+    pub fn soft_invalidate(&mut self, input: &TIn)
+    where
+        TIn: Clone + Eq + Hash,
+    {
+        let cached = self
+            .values
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains_key(input);
+        if cached {
+            self.stale.insert(input.clone());
+        }
+    }
+
+    /// Block until every background recomputation has finished.
+    ///
+    /// # Panics
+    /// Resumes the panic of a resolver that panicked on a background thread,
+    /// including refreshes which had already finished before this call.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SoftMemory;
+    /// let mut doubler = SoftMemory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// doubler.soft_invalidate(&2);
+    /// doubler.resolve(&2);
+    /// doubler.wait();
+    /// ```
+    pub fn wait(&mut self) {
+        for handle in mem::take(&mut self.refreshing) {
+            self.join(handle);
+        }
+        if let Some(panic) = self.panicked.take() {
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    fn refresh(&mut self, input: TIn)
+    where
+        TIn: Eq + Hash + Send + 'static,
+        TOut: Send + 'static,
+    {
+        let (finished, running) = mem::take(&mut self.refreshing)
+            .into_iter()
+            .partition(JoinHandle::is_finished);
+        self.refreshing = running;
+        for handle in finished {
+            self.join(handle);
+        }
+
+        // Refreshes can finish out of order, only the latest one is kept
+        self.generation += 1;
+        let generation = self.generation;
+        let remember = Arc::clone(&self.remember);
+        let values = Arc::clone(&self.values);
+        self.refreshing.push(thread::spawn(move || {
+            let result = remember(&input);
+            let mut values = values.lock().unwrap_or_else(PoisonError::into_inner);
+            match values.get_mut(&input) {
+                Some(entry) if entry.1 > generation => {}
+                Some(entry) => *entry = (result, generation),
+                None => {
+                    values.insert(input, (result, generation));
+                }
+            }
+        }));
+    }
+
+    /// Join a refresh, keeping its panic until the next `wait`.
+    fn join(&mut self, handle: JoinHandle<()>) {
+        if let Err(panic) = handle.join() {
+            self.panicked.get_or_insert(panic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        mpsc,
+    };
+
+    #[test]
+    fn it_serves_the_stale_value_immediately() {
+        let (release, gate) = mpsc::channel::<()>();
+        let gate = Mutex::new(gate);
+        let generation = AtomicU32::new(0);
+        let mut memory = SoftMemory::new(move |key: &u32| {
+            let current = generation.fetch_add(1, Ordering::SeqCst);
+            if current > 0 {
+                // Hold the background refresh until the test allows it
+                gate.lock().unwrap().recv().unwrap();
+            }
+            key * 2 + current
+        });
+
+        assert_eq!(memory.resolve(&2), 4);
+        memory.soft_invalidate(&2);
+        assert_eq!(memory.resolve(&2), 4); // Stale, refresh is still blocked
+        assert_eq!(memory.resolve(&2), 4);
+
+        release.send(()).unwrap();
+        memory.wait();
+        assert_eq!(memory.resolve(&2), 5);
+    }
+
+    #[test]
+    fn it_ignores_soft_invalidation_of_missing_entries() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let mut memory = SoftMemory::new(move |key: &u32| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            key * 2
+        });
+
+        memory.soft_invalidate(&2);
+        assert_eq!(memory.resolve(&2), 4);
+        assert_eq!(memory.resolve(&2), 4);
+        memory.wait();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn it_keeps_the_latest_of_overlapping_refreshes() {
+        let (started, first_started) = mpsc::channel();
+        let (release, gate) = mpsc::channel::<()>();
+        let (started, gate) = (Mutex::new(started), Mutex::new(gate));
+        let generation = AtomicU32::new(0);
+        let mut memory = SoftMemory::new(move |key: &u32| {
+            let current = generation.fetch_add(1, Ordering::SeqCst);
+            if current == 1 {
+                // Hold the first refresh until the second one is stored
+                started.lock().unwrap().send(()).unwrap();
+                gate.lock().unwrap().recv().unwrap();
+            }
+            key * 2 + current
+        });
+        assert_eq!(memory.resolve(&2), 4);
+
+        memory.soft_invalidate(&2);
+        memory.resolve(&2);
+        first_started.recv().unwrap();
+
+        memory.soft_invalidate(&2);
+        memory.resolve(&2);
+        while memory.resolve(&2) != 6 {
+            thread::yield_now();
+        }
+
+        release.send(()).unwrap();
+        memory.wait();
+        assert_eq!(memory.resolve(&2), 6); // The older refresh was dropped
+    }
+
+    #[test]
+    #[should_panic(expected = "flaky resolver")]
+    fn it_keeps_the_panic_of_a_finished_refresh() {
+        let generation = AtomicU32::new(0);
+        let mut memory = SoftMemory::new(move |key: &u32| {
+            let current = generation.fetch_add(1, Ordering::SeqCst);
+            assert!(current != 1, "flaky resolver");
+            key * 2
+        });
+        memory.resolve(&2);
+
+        memory.soft_invalidate(&2);
+        memory.resolve(&2);
+        while !memory.refreshing[0].is_finished() {
+            thread::yield_now();
+        }
+
+        // Starting another refresh collects the one that panicked
+        memory.soft_invalidate(&2);
+        memory.resolve(&2);
+        memory.wait();
+    }
+}
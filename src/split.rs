@@ -0,0 +1,170 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A memory split between shared readers and an exclusive writer.
+///
+/// Any number of readers can peek at cached values at the same time,
+/// while adding new entries requires exclusive access through the writer.
+/// Both are plain borrows, so no lock is taken on either side.
+pub struct SplitMemory<T, TIn, TOut> {
+    remember: T,
+    values: HashMap<TIn, TOut>,
+}
+
+/// Shared, read-only access to the entries of a [`SplitMemory`].
+pub struct MemoryReader<'a, TIn, TOut> {
+    values: &'a HashMap<TIn, TOut>,
+}
+
+/// Exclusive access to a [`SplitMemory`], resolving misses.
+pub struct MemoryWriter<'a, T, TIn, TOut> {
+    remember: &'a mut T,
+    values: &'a mut HashMap<TIn, TOut>,
+}
+
+impl<T, TIn, TOut> SplitMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Create a new `SplitMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SplitMemory;
+    /// let mut doubler = SplitMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.writer().resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self {
+            remember,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Get shared, read-only access to the cached entries.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SplitMemory;
+    /// let doubler = SplitMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.reader().peek(&2), None);
+    /// ```
+    pub const fn reader(&self) -> MemoryReader<'_, TIn, TOut> {
+        MemoryReader {
+            values: &self.values,
+        }
+    }
+
+    /// Get exclusive access to the memory, to resolve new entries.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SplitMemory;
+    /// let mut doubler = SplitMemory::new(|x: &u32| x * 2);
+    /// doubler.writer().resolve(&2);
+    /// assert_eq!(doubler.reader().peek(&2), Some(&4));
+    /// ```
+    pub const fn writer(&mut self) -> MemoryWriter<'_, T, TIn, TOut> {
+        MemoryWriter {
+            remember: &mut self.remember,
+            values: &mut self.values,
+        }
+    }
+}
+
+impl<TIn, TOut> MemoryReader<'_, TIn, TOut> {
+    /// Look up the cached value of this input, without resolving anything.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SplitMemory;
+    /// let mut doubler = SplitMemory::new(|x: &u32| x * 2);
+    /// doubler.writer().resolve(&2);
+    ///
+    /// let reader = doubler.reader();
+    /// assert_eq!(reader.peek(&2), Some(&4));
+    /// assert_eq!(reader.peek(&3), None);
+    /// ```
+    pub fn peek(&self, input: &TIn) -> Option<&TOut>
+    where
+        TIn: Eq + Hash,
+    {
+        self.values.get(input)
+    }
+}
+
+impl<T, TIn, TOut> MemoryWriter<'_, T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result of the resolver is stored with the input value as the key.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SplitMemory;
+    /// let mut doubler = SplitMemory::new(|x: &u32| x * 2);
+    /// let mut writer = doubler.writer();
+    /// assert_eq!(writer.resolve(&2), 4);
+    /// assert_eq!(writer.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.values.get(input) {
+            value.clone()
+        } else {
+            let result = (self.remember)(input);
+            self.values.insert(input.clone(), result.clone());
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn it_resolves_through_the_writer() {
+        let mut counter = 0;
+        let mut memory = SplitMemory::new(|key: &u32| {
+            counter += 1;
+            key * 2
+        });
+
+        let mut writer = memory.writer();
+        assert_eq!(writer.resolve(&2), 4);
+        assert_eq!(writer.resolve(&2), 4);
+        assert_eq!(memory.reader().peek(&2), Some(&4));
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_allows_concurrent_readers() {
+        let mut memory = SplitMemory::new(|key: &u32| key * 2);
+        let mut writer = memory.writer();
+        for key in 0..10 {
+            writer.resolve(&key);
+        }
+
+        let memory = &memory;
+        thread::scope(|scope| {
+            let readers: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(move || {
+                        let reader = memory.reader();
+                        (0..10).all(|key| reader.peek(&key) == Some(&(key * 2)))
+                    })
+                })
+                .collect();
+
+            for reader in readers {
+                assert!(reader.join().unwrap());
+            }
+        });
+    }
+}
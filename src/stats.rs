@@ -0,0 +1,39 @@
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+/// Bookkeeping about the resolver calls of a [`Memory`](crate::Memory).
+pub struct Stats<TIn> {
+    /// How many times the resolver was called for each input.
+    pub misses: HashMap<TIn, u64>,
+    /// How many times the resolver was called in total.
+    pub resolver_calls: u64,
+    /// How long the resolver calls took altogether.
+    pub resolver_time: Duration,
+}
+
+impl<TIn> Stats<TIn> {
+    pub fn new() -> Self {
+        Self {
+            misses: HashMap::new(),
+            resolver_calls: 0,
+            resolver_time: Duration::ZERO,
+        }
+    }
+
+    /// Carry the stats over to inputs converted by `f`, adding up the misses of merged inputs.
+    pub fn map_keys<U>(self, f: impl Fn(TIn) -> U) -> Stats<U>
+    where
+        U: Eq + Hash,
+    {
+        Stats {
+            misses: self
+                .misses
+                .into_iter()
+                .fold(HashMap::new(), |mut misses, (key, count)| {
+                    *misses.entry(f(key)).or_insert(0) += count;
+                    misses
+                }),
+            resolver_calls: self.resolver_calls,
+            resolver_time: self.resolver_time,
+        }
+    }
+}
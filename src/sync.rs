@@ -0,0 +1,128 @@
+use crate::concurrency_limited::ConcurrencyLimitedMemory;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Mutex, PoisonError},
+};
+
+/// A memory that can be shared between threads.
+///
+/// Resolving only needs `&self`, so the memory can sit behind an `Arc`
+/// or be borrowed by scoped threads.
+/// The lock isn't held while the resolver runs,
+/// so two threads missing on the same input may both call it.
+pub struct SyncMemory<T, TIn, TOut> {
+    pub(crate) remember: T,
+    values: Mutex<HashMap<TIn, TOut>>,
+}
+
+impl<T, TIn, TOut> SyncMemory<T, TIn, TOut>
+where
+    T: Fn(&TIn) -> TOut,
+{
+    /// Create a new `SyncMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SyncMemory;
+    /// let doubler = SyncMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self {
+            remember,
+            values: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result of the resolver is stored with the input value as the key.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SyncMemory;
+    /// let doubler = SyncMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.cached(input) {
+            return value;
+        }
+        let result = (self.remember)(input);
+        self.store(input.clone(), result.clone());
+        result
+    }
+
+    /// Bound how many resolver calls may run at the same time.
+    ///
+    /// # Panics
+    /// Panics if `max_concurrent` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::SyncMemory;
+    /// let doubler = SyncMemory::new(|x: &u32| x * 2).with_concurrency_limit(4);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn with_concurrency_limit(
+        self,
+        max_concurrent: usize,
+    ) -> ConcurrencyLimitedMemory<T, TIn, TOut> {
+        ConcurrencyLimitedMemory::new(self, max_concurrent)
+    }
+
+    pub(crate) fn cached(&self, input: &TIn) -> Option<TOut>
+    where
+        TIn: Eq + Hash,
+        TOut: Clone,
+    {
+        self.values
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(input)
+            .cloned()
+    }
+
+    pub(crate) fn store(&self, input: TIn, value: TOut)
+    where
+        TIn: Eq + Hash,
+    {
+        self.values
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(input, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        thread,
+    };
+
+    #[test]
+    fn it_resolves_from_many_threads() {
+        let calls = AtomicU32::new(0);
+        let memory = SyncMemory::new(|key: &u32| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            key * 2
+        });
+        memory.resolve(&1);
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(memory.resolve(&1), 2));
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
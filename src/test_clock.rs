@@ -0,0 +1,21 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static START: Instant = Instant::now();
+    static ELAPSED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// The time on a clock for tests, which only moves with [`advance`].
+///
+/// Every test thread has its own clock, so tests can't move each other's.
+pub fn mock_now() -> Instant {
+    START.with(|start| *start + ELAPSED.with(Cell::get))
+}
+
+/// Move the test clock of this thread forward.
+pub fn advance(by: Duration) {
+    ELAPSED.with(|elapsed| elapsed.set(elapsed.get() + by));
+}
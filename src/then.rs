@@ -0,0 +1,75 @@
+use crate::recall::Recall;
+
+/// A [`Recall`] running a follow-up action with the value on every read.
+///
+/// Created with [`Recall::then`].
+pub struct ThenRecall<T, F, TOut> {
+    recall: Recall<T, TOut>,
+    then: F,
+}
+
+impl<T, F, TOut> ThenRecall<T, F, TOut>
+where
+    T: FnMut() -> TOut,
+    F: FnMut(&TOut),
+    TOut: Clone,
+{
+    pub(crate) const fn new(recall: Recall<T, TOut>, then: F) -> Self {
+        Self { recall, then }
+    }
+
+    /// Resolve the lazy-evaluated function, run the follow-up action and return the value.
+    ///
+    /// The evaluation only happens once, but the follow-up runs on every call.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Recall;
+    /// let mut reads = 0;
+    /// let mut answer = Recall::new(|| 42).then(|_| reads += 1);
+    /// assert_eq!(answer.value(), 42);
+    /// assert_eq!(answer.value(), 42);
+    /// drop(answer);
+    /// assert_eq!(reads, 2);
+    /// ```
+    pub fn value(&mut self) -> TOut {
+        let value = self.recall.value();
+        (self.then)(&value);
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Recall;
+
+    #[test]
+    fn it_runs_the_follow_up_on_every_read() {
+        let mut evaluations = 0;
+        let mut seen = Vec::new();
+        let mut recall = Recall::new(|| {
+            evaluations += 1;
+            42
+        })
+        .then(|value| seen.push(*value));
+
+        assert_eq!(recall.value(), 42);
+        assert_eq!(recall.value(), 42);
+        assert_eq!(recall.value(), 42);
+
+        assert_eq!(evaluations, 1);
+        assert_eq!(seen, [42, 42, 42]);
+    }
+
+    #[test]
+    fn it_runs_the_follow_up_after_an_earlier_evaluation() {
+        let mut recall = Recall::new(|| 42);
+        recall.value();
+
+        let mut reads = 0;
+        let mut then = recall.then(|_| reads += 1);
+        assert_eq!(then.value(), 42);
+
+        assert_eq!(reads, 1);
+    }
+}
@@ -0,0 +1,167 @@
+use std::{collections::HashMap, hash::Hash, time::Instant};
+
+/// A memory remembering when each entry was stored, for batch expiry.
+///
+/// Unlike [`SlidingWindowMemory`](crate::SlidingWindowMemory),
+/// nothing expires on its own: old entries are only removed by
+/// [`TimestampedMemory::expire_before`], e.g. called periodically.
+pub struct TimestampedMemory<T, TIn, TOut> {
+    remember: T,
+    entries: HashMap<TIn, (TOut, Instant)>,
+    now: fn() -> Instant,
+}
+
+impl<T, TIn, TOut> TimestampedMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Create a new `TimestampedMemory`, providing a function for handling a cache miss
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TimestampedMemory;
+    /// let mut doubler = TimestampedMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T) -> Self {
+        Self::with_clock(remember, Instant::now)
+    }
+
+    pub(crate) fn with_clock(remember: T, now: fn() -> Instant) -> Self {
+        Self {
+            remember,
+            entries: HashMap::new(),
+            now,
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result is stored along with the current time.
+    /// A hit doesn't change when the entry was stored.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TimestampedMemory;
+    /// let mut doubler = TimestampedMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some((value, _)) = self.entries.get(input) {
+            return value.clone();
+        }
+        let result = (self.remember)(input);
+        self.entries
+            .insert(input.clone(), (result.clone(), (self.now)()));
+        result
+    }
+
+    /// When the value of this input was stored, if it's cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TimestampedMemory;
+    /// let mut doubler = TimestampedMemory::new(|x: &u32| x * 2);
+    /// assert_eq!(doubler.stored_at(&2), None);
+    /// doubler.resolve(&2);
+    /// assert!(doubler.stored_at(&2).is_some());
+    /// ```
+    pub fn stored_at(&self, input: &TIn) -> Option<Instant>
+    where
+        TIn: Eq + Hash,
+    {
+        self.entries.get(input).map(|(_, stored)| *stored)
+    }
+
+    /// Remove every entry stored before `cutoff`.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TimestampedMemory;
+    /// use std::time::{Duration, Instant};
+    /// let mut doubler = TimestampedMemory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let ttl = Duration::from_secs(60);
+    /// doubler.expire_before(Instant::now() - ttl);
+    /// assert!(!doubler.is_empty()); // Still fresh
+    ///
+    /// doubler.expire_before(Instant::now() + ttl);
+    /// assert!(doubler.is_empty());
+    /// ```
+    pub fn expire_before(&mut self, cutoff: Instant) {
+        self.entries.retain(|_, (_, stored)| *stored >= cutoff);
+    }
+
+    /// How many entries are cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TimestampedMemory;
+    /// let mut doubler = TimestampedMemory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether nothing is cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TimestampedMemory;
+    /// let doubler = TimestampedMemory::new(|x: &u32| x * 2);
+    /// assert!(doubler.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_clock::{advance, mock_now};
+    use std::time::Duration;
+
+    #[test]
+    fn it_expires_entries_stored_before_the_cutoff() {
+        let mut counter = 0;
+        let mut memory = TimestampedMemory::with_clock(
+            |x: &u32| {
+                counter += 1;
+                x * 2
+            },
+            mock_now,
+        );
+
+        memory.resolve(&1);
+        memory.resolve(&2);
+        advance(Duration::from_secs(10));
+        let cutoff = mock_now();
+        memory.resolve(&3);
+        advance(Duration::from_secs(10));
+        memory.resolve(&1); // A hit isn't a new store
+        memory.resolve(&4);
+
+        memory.expire_before(cutoff);
+        assert_eq!(memory.len(), 2);
+        assert_eq!(memory.stored_at(&1), None);
+        assert_eq!(memory.stored_at(&2), None);
+        assert_eq!(memory.stored_at(&3), Some(cutoff));
+        assert!(memory.stored_at(&4).is_some());
+
+        assert_eq!(memory.resolve(&1), 2);
+        drop(memory);
+        assert_eq!(counter, 5);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::memoize::Memoize;
+use std::marker::PhantomData;
+
+/// A memory that never remembers anything, calling its resolver every time.
+///
+/// Created with [`Memory::transparent`](crate::Memory::transparent).
+/// Useful to measure what the cache actually saves, since it can stand in
+/// for a [`Memory`](crate::Memory) wherever a [`Memoize`] is expected.
+pub struct TransparentMemory<T, TIn, TOut> {
+    remember: T,
+    _types: PhantomData<fn(&TIn) -> TOut>,
+}
+
+impl<T, TIn, TOut> TransparentMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    pub(crate) const fn new(remember: T) -> Self {
+        Self {
+            remember,
+            _types: PhantomData,
+        }
+    }
+
+    /// Call the resolver for this input.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::transparent(|x: &u32| x * 2);
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Recalculated
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut {
+        (self.remember)(input)
+    }
+}
+
+impl<T, TIn, TOut> Memoize<TIn, TOut> for TransparentMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    fn resolve(&mut self, input: &TIn) -> TOut {
+        Self::resolve(self, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Memory;
+
+    fn resolve_twice(memory: &mut impl Memoize<u32, u32>) {
+        memory.resolve(&1);
+        memory.resolve(&1);
+    }
+
+    #[test]
+    fn it_calls_the_resolver_every_time() {
+        let mut counter = 0;
+        let mut memory = Memory::transparent(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+        for _ in 0..5 {
+            assert_eq!(memory.resolve(&2), 4);
+        }
+
+        resolve_twice(&mut memory);
+        assert_eq!(counter, 7);
+    }
+
+    #[test]
+    fn it_stands_in_for_a_memory() {
+        let mut cached_calls = 0;
+        let mut transparent_calls = 0;
+        resolve_twice(&mut Memory::new(|x: &u32| {
+            cached_calls += 1;
+            x * 2
+        }));
+        resolve_twice(&mut Memory::transparent(|x: &u32| {
+            transparent_calls += 1;
+            x * 2
+        }));
+
+        assert_eq!(cached_calls, 1);
+        assert_eq!(transparent_calls, 2);
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    num::NonZeroUsize,
+};
+
+/// A bounded memory evicting entries with the 2Q policy.
+///
+/// Inputs resolved once wait in a FIFO queue, and are promoted to an LRU list
+/// when resolved again while still cached.
+/// Eviction takes from the FIFO queue first, so a scan over many one-off inputs
+/// can't push out the entries that are actually reused.
+pub struct TwoQueueMemory<T, TIn, TOut> {
+    remember: T,
+    capacity: NonZeroUsize,
+    values: HashMap<TIn, Slot<TOut>>,
+    // Queued inputs are tagged with the stamp of their slot at the time,
+    // a promotion leaves the old position behind instead of searching for it
+    fifo_queue: VecDeque<(TIn, u64)>,
+    lru_list: VecDeque<(TIn, u64)>,
+    fifo_len: usize,
+    next_stamp: u64,
+}
+
+struct Slot<TOut> {
+    value: TOut,
+    in_lru: bool,
+    stamp: u64,
+}
+
+impl<T, TIn, TOut> TwoQueueMemory<T, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+{
+    /// Create a new `TwoQueueMemory`, holding at most `capacity` entries
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TwoQueueMemory;
+    /// use std::num::NonZeroUsize;
+    /// let mut doubler = TwoQueueMemory::new(|x: &u32| x * 2, NonZeroUsize::new(100).unwrap());
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn new(remember: T, capacity: NonZeroUsize) -> Self {
+        Self {
+            remember,
+            capacity,
+            values: HashMap::new(),
+            fifo_queue: VecDeque::new(),
+            lru_list: VecDeque::new(),
+            fifo_len: 0,
+            next_stamp: 0,
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result enters the FIFO queue, evicting an entry if the memory is full.
+    /// On a hit, the entry moves to the most recently used end of the LRU list.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TwoQueueMemory;
+    /// use std::num::NonZeroUsize;
+    /// let mut doubler = TwoQueueMemory::new(|x: &u32| x * 2, NonZeroUsize::new(100).unwrap());
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.resolve(&2), 4); // Didn't recalculate
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(slot) = self.values.get_mut(input) {
+            // Move the input to the most recently used end of the LRU list
+            self.next_stamp += 1;
+            if !slot.in_lru {
+                self.fifo_len -= 1;
+            }
+            slot.in_lru = true;
+            slot.stamp = self.next_stamp;
+            let value = slot.value.clone();
+            self.lru_list.push_back((input.clone(), self.next_stamp));
+            self.compact();
+            return value;
+        }
+
+        if self.values.len() >= self.capacity.get() {
+            self.evict();
+        }
+        let result = (self.remember)(input);
+        self.next_stamp += 1;
+        let slot = Slot {
+            value: result.clone(),
+            in_lru: false,
+            stamp: self.next_stamp,
+        };
+        self.values.insert(input.clone(), slot);
+        self.fifo_queue.push_back((input.clone(), self.next_stamp));
+        self.fifo_len += 1;
+        result
+    }
+
+    /// How many entries are cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TwoQueueMemory;
+    /// use std::num::NonZeroUsize;
+    /// let mut doubler = TwoQueueMemory::new(|x: &u32| x * 2, NonZeroUsize::new(2).unwrap());
+    /// for x in 0..5 {
+    ///     doubler.resolve(&x);
+    /// }
+    /// assert_eq!(doubler.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether nothing is cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::TwoQueueMemory;
+    /// use std::num::NonZeroUsize;
+    /// let doubler = TwoQueueMemory::new(|x: &u32| x * 2, NonZeroUsize::new(2).unwrap());
+    /// assert!(doubler.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Remove one entry, from the FIFO queue while it holds more than its share.
+    fn evict(&mut self)
+    where
+        TIn: Eq + Hash,
+    {
+        let fifo_share = (self.capacity.get() / 4).max(1);
+        let lru_len = self.values.len() - self.fifo_len;
+        let from_fifo = self.fifo_len > fifo_share || lru_len == 0;
+        let queue = if from_fifo {
+            &mut self.fifo_queue
+        } else {
+            &mut self.lru_list
+        };
+
+        // Skip the positions left behind by promotions
+        while let Some((key, stamp)) = queue.pop_front() {
+            if self
+                .values
+                .get(&key)
+                .is_some_and(|slot| slot.stamp == stamp)
+            {
+                self.values.remove(&key);
+                if from_fifo {
+                    self.fifo_len -= 1;
+                }
+                return;
+            }
+        }
+    }
+
+    /// Drop the positions left behind by promotions, once they outnumber the entries.
+    ///
+    /// Both queues are only swept after growing past twice the capacity,
+    /// so a hit stays constant time on average.
+    fn compact(&mut self)
+    where
+        TIn: Eq + Hash,
+    {
+        let limit = 2 * self.capacity.get();
+        let values = &self.values;
+        for queue in [&mut self.fifo_queue, &mut self.lru_list] {
+            if queue.len() > limit {
+                queue.retain(|(key, stamp)| {
+                    values.get(key).is_some_and(|slot| slot.stamp == *stamp)
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plain LRU memory, to compare against.
+    struct LruMemory {
+        capacity: usize,
+        recent: VecDeque<u32>,
+        misses: u32,
+    }
+
+    impl LruMemory {
+        fn resolve(&mut self, input: u32) {
+            if let Some(position) = self.recent.iter().position(|key| *key == input) {
+                self.recent.remove(position);
+            } else {
+                self.misses += 1;
+                if self.recent.len() >= self.capacity {
+                    self.recent.pop_front();
+                }
+            }
+            self.recent.push_back(input);
+        }
+    }
+
+    /// Two hot inputs, reused between scans over inputs only seen once.
+    fn scan_pattern() -> Vec<u32> {
+        let mut pattern = vec![1, 2, 1, 2];
+        for scan in 0..20 {
+            pattern.extend([100 + scan * 3, 101 + scan * 3, 102 + scan * 3, 1, 2]);
+        }
+        pattern
+    }
+
+    #[test]
+    fn it_keeps_reused_entries_through_scans() {
+        let capacity = NonZeroUsize::new(4).unwrap();
+        let mut misses = 0;
+        let mut memory = TwoQueueMemory::new(
+            |x: &u32| {
+                misses += 1;
+                x * 2
+            },
+            capacity,
+        );
+        let mut lru = LruMemory {
+            capacity: capacity.get(),
+            recent: VecDeque::new(),
+            misses: 0,
+        };
+
+        for input in scan_pattern() {
+            assert_eq!(memory.resolve(&input), input * 2);
+            lru.resolve(input);
+        }
+        assert_eq!(memory.len(), 4);
+
+        // Only the first sight of each input misses with 2Q
+        assert_eq!(misses, 2 + 20 * 3);
+        assert!(lru.misses > misses, "LRU missed {} times", lru.misses);
+    }
+
+    #[test]
+    fn it_evicts_from_the_lru_list_without_one_off_entries() {
+        let mut memory = TwoQueueMemory::new(|x: &u32| x * 2, NonZeroUsize::new(2).unwrap());
+        for input in [1, 1, 2, 2, 1, 3] {
+            memory.resolve(&input);
+        }
+
+        assert_eq!(memory.len(), 2);
+        assert!(memory.values.contains_key(&1));
+        assert!(memory.values.contains_key(&3));
+    }
+
+    #[test]
+    fn it_keeps_the_queues_bounded_under_repeated_hits() {
+        let mut memory = TwoQueueMemory::new(|x: &u32| x * 2, NonZeroUsize::new(4).unwrap());
+        for round in 0..1000 {
+            memory.resolve(&(round % 3));
+        }
+
+        assert_eq!(memory.len(), 3);
+        assert!(
+            memory.lru_list.len() <= 8,
+            "{} positions",
+            memory.lru_list.len()
+        );
+        assert!(
+            memory.fifo_queue.len() <= 8,
+            "{} positions",
+            memory.fifo_queue.len()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::memory::Memory;
+use std::{collections::HashMap, hash::Hash};
+
+/// A tentative update of a single cached value.
+///
+/// Returned by [`Memory::begin_update`](crate::Memory::begin_update).
+/// The new value is visible as soon as the update begins,
+/// and stays there once [`UpdateToken::commit`] is called.
+/// Rolling back, or dropping the token without committing, restores the previous state.
+///
+/// The token borrows the memory, so only one update can be pending at a time.
+#[must_use = "dropping the token rolls the update back"]
+pub struct UpdateToken<'a, TIn: Eq + Hash, TOut> {
+    values: &'a mut HashMap<TIn, TOut>,
+    #[cfg(feature = "ordering")]
+    order: &'a mut Vec<TIn>,
+    key: TIn,
+    previous: Option<TOut>,
+    pending: bool,
+}
+
+impl<'a, TIn, TOut> UpdateToken<'a, TIn, TOut>
+where
+    TIn: Eq + Hash,
+{
+    pub(crate) fn new<T>(memory: &'a mut Memory<T, TIn, TOut>, key: TIn, value: TOut) -> Self
+    where
+        TIn: Clone,
+    {
+        #[cfg(feature = "ordering")]
+        if !memory.values.contains_key(&key) {
+            memory.order.push(key.clone());
+        }
+        let previous = memory.values.insert(key.clone(), value);
+        Self {
+            values: &mut memory.values,
+            #[cfg(feature = "ordering")]
+            order: &mut memory.order,
+            key,
+            previous,
+            pending: true,
+        }
+    }
+
+    /// Keep the new value.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.begin_update(2, 5).commit();
+    /// assert_eq!(doubler.resolve(&2), 5);
+    /// ```
+    pub fn commit(mut self) {
+        self.pending = false;
+    }
+
+    /// Restore the value cached before the update, or remove the entry if there wasn't one.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// doubler.begin_update(2, 5).rollback();
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// ```
+    pub fn rollback(self) {}
+}
+
+impl<TIn, TOut> Drop for UpdateToken<'_, TIn, TOut>
+where
+    TIn: Eq + Hash,
+{
+    fn drop(&mut self) {
+        if !self.pending {
+            return;
+        }
+        if let Some(previous) = self.previous.take() {
+            if let Some(value) = self.values.get_mut(&self.key) {
+                *value = previous;
+            }
+            return;
+        }
+        self.values.remove(&self.key);
+        // The key was new, so it was the last one recorded
+        #[cfg(feature = "ordering")]
+        self.order.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CacheStatus;
+
+    #[test]
+    fn it_keeps_committed_updates() {
+        let mut counter = 0;
+        let mut memory = Memory::new(|x: &u32| {
+            counter += 1;
+            x * 2
+        });
+        memory.resolve(&1);
+
+        memory.begin_update(1, 10).commit();
+        memory.begin_update(2, 20).commit();
+
+        assert_eq!(memory.resolve(&1), 10);
+        assert_eq!(memory.resolve(&2), 20);
+
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_reverts_rolled_back_updates() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+
+        memory.begin_update(1, 10).rollback();
+        memory.begin_update(2, 20).rollback();
+
+        assert_eq!(memory.probe(&2), CacheStatus::Miss);
+        assert_eq!(memory.resolve(&1), 2);
+    }
+
+    #[test]
+    fn it_shows_the_new_value_while_pending() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+
+        {
+            let token = memory.begin_update(1, 10);
+            assert_eq!(token.values[&1], 10);
+        }
+
+        assert_eq!(memory.resolve(&1), 2);
+    }
+
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn it_forgets_the_order_of_rolled_back_inputs() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+
+        memory.begin_update(2, 20).rollback();
+        memory.begin_update(3, 30).commit();
+
+        assert_eq!(memory.access_order(), [&1, &3]);
+    }
+}
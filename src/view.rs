@@ -0,0 +1,106 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+/// A read-only view over the cached entries of a [`Memory`](crate::Memory).
+///
+/// Created with [`Memory::observe`](crate::Memory::observe).
+/// The view only borrows the entries, so it's `Copy`
+/// and can be shared between scoped threads without any locking.
+/// The memory can't be changed while a view of it is alive.
+pub struct MemoryView<'a, TIn, TOut> {
+    values: &'a HashMap<TIn, TOut>,
+}
+
+impl<TIn, TOut> Clone for MemoryView<'_, TIn, TOut> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<TIn, TOut> Copy for MemoryView<'_, TIn, TOut> {}
+
+impl<'a, TIn, TOut> MemoryView<'a, TIn, TOut> {
+    pub(crate) const fn new(values: &'a HashMap<TIn, TOut>) -> Self {
+        Self { values }
+    }
+
+    /// The cached value for this input, if there is one.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    ///
+    /// let view = doubler.observe();
+    /// assert_eq!(view.get(&2), Some(&4));
+    /// assert_eq!(view.get(&3), None);
+    /// ```
+    pub fn get<Q>(&self, input: &Q) -> Option<&'a TOut>
+    where
+        TIn: Borrow<Q> + Eq + Hash,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.values.get(input)
+    }
+
+    /// How many entries are cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2);
+    /// doubler.resolve(&2);
+    /// assert_eq!(doubler.observe().len(), 1);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether nothing is cached.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let doubler = Memory::new(|x: &u32| x * 2);
+    /// assert!(doubler.observe().is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Memory;
+    use std::thread;
+
+    #[test]
+    fn it_reflects_changes_made_before_it_was_created() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        memory.resolve(&1);
+        assert_eq!(memory.observe().get(&2), None);
+
+        memory.resolve(&2);
+        let view = memory.observe();
+        assert_eq!(view.get(&1), Some(&2));
+        assert_eq!(view.get(&2), Some(&4));
+        assert_eq!(view.len(), 2);
+    }
+
+    #[test]
+    fn it_is_shared_between_threads() {
+        let mut memory = Memory::new(|x: &u32| x * 2);
+        for x in 0..10 {
+            memory.resolve(&x);
+        }
+
+        let view = memory.observe();
+        thread::scope(|scope| {
+            for x in 0..10 {
+                scope.spawn(move || assert_eq!(view.get(&x), Some(&(x * 2))));
+            }
+        });
+    }
+}
@@ -0,0 +1,176 @@
+use crate::memory::Memory;
+use std::hash::Hash;
+
+/// A memory that batches newly computed entries for a persistent store.
+///
+/// Entries are written to the store when the memory is flushed,
+/// rather than on every miss.
+pub struct WriteBackMemory<T, W, TIn, TOut> {
+    memory: Memory<T, TIn, TOut>,
+    writer: W,
+    dirty: Vec<TIn>,
+}
+
+impl<T, W, TIn, TOut> WriteBackMemory<T, W, TIn, TOut>
+where
+    T: FnMut(&TIn) -> TOut,
+    W: FnMut(&[(TIn, TOut)]),
+{
+    pub(crate) const fn new(memory: Memory<T, TIn, TOut>, writer: W) -> Self {
+        Self {
+            memory,
+            writer,
+            dirty: Vec::new(),
+        }
+    }
+
+    /// Search the memory for the previous result of this input
+    ///
+    /// On a miss, the result is stored and queued for the next flush.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).write_back(|_: &[(u32, u32)]| {});
+    /// assert_eq!(doubler.resolve(&2), 4);
+    /// assert_eq!(doubler.pending(), 1);
+    /// ```
+    pub fn resolve(&mut self, input: &TIn) -> TOut
+    where
+        TIn: Clone + Eq + Hash,
+        TOut: Clone,
+    {
+        if let Some(value) = self.memory.values.get(input) {
+            value.clone()
+        } else {
+            let result = self.memory.compute(input);
+            self.memory.store(input.clone(), result.clone());
+            self.dirty.push(input.clone());
+            result
+        }
+    }
+
+    /// Write every pending entry to the store, in a single batch.
+    ///
+    /// The writer is not called when nothing is pending.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut store = Vec::new();
+    /// let mut doubler = Memory::new(|x: &u32| x * 2)
+    ///     .write_back(|batch: &[(u32, u32)]| store.extend_from_slice(batch));
+    ///
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    /// doubler.flush();
+    /// drop(doubler);
+    /// assert_eq!(store, [(1, 2), (2, 4)]);
+    /// ```
+    pub fn flush(&mut self)
+    where
+        TIn: Eq + Hash,
+        TOut: Clone,
+    {
+        if self.dirty.is_empty() {
+            return;
+        }
+
+        let batch: Vec<_> = self
+            .dirty
+            .drain(..)
+            .filter_map(|key| {
+                let value = self.memory.values.get(&key)?.clone();
+                Some((key, value))
+            })
+            .collect();
+        (self.writer)(&batch);
+    }
+
+    /// Write the entry of a single input to the store, if it is pending.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut store = Vec::new();
+    /// let mut doubler = Memory::new(|x: &u32| x * 2)
+    ///     .write_back(|batch: &[(u32, u32)]| store.extend_from_slice(batch));
+    ///
+    /// doubler.resolve(&1);
+    /// doubler.resolve(&2);
+    /// doubler.flush_key(&2);
+    /// drop(doubler);
+    /// assert_eq!(store, [(2, 4)]);
+    /// ```
+    pub fn flush_key(&mut self, input: &TIn)
+    where
+        TIn: Eq + Hash,
+        TOut: Clone,
+    {
+        let Some(position) = self.dirty.iter().position(|key| key == input) else {
+            return;
+        };
+
+        let key = self.dirty.remove(position);
+        if let Some(value) = self.memory.values.get(&key) {
+            let value = value.clone();
+            (self.writer)(&[(key, value)]);
+        }
+    }
+
+    /// How many entries are waiting for the next flush.
+    ///
+    /// # Examples
+    /// ```
+    /// use souvenir::Memory;
+    /// let mut doubler = Memory::new(|x: &u32| x * 2).write_back(|_: &[(u32, u32)]| {});
+    /// assert_eq!(doubler.pending(), 0);
+    /// ```
+    pub const fn pending(&self) -> usize {
+        self.dirty.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Memory;
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_only_writes_on_flush() {
+        let batches = RefCell::new(Vec::new());
+        let mut memory = Memory::new(|key: &u32| key * 2)
+            .write_back(|batch: &[(u32, u32)]| batches.borrow_mut().push(batch.to_vec()));
+
+        memory.resolve(&1);
+        memory.resolve(&2);
+        memory.resolve(&1);
+        assert!(batches.borrow().is_empty());
+        assert_eq!(memory.pending(), 2);
+
+        memory.flush();
+        assert_eq!(*batches.borrow(), [vec![(1, 2), (2, 4)]]);
+        assert_eq!(memory.pending(), 0);
+
+        memory.flush();
+        memory.resolve(&2);
+        assert_eq!(batches.borrow().len(), 1);
+    }
+
+    #[test]
+    fn it_flushes_single_keys() {
+        let batches = RefCell::new(Vec::new());
+        let mut memory = Memory::new(|key: &u32| key * 2)
+            .write_back(|batch: &[(u32, u32)]| batches.borrow_mut().push(batch.to_vec()));
+
+        memory.resolve(&1);
+        memory.resolve(&2);
+        memory.flush_key(&2);
+        memory.flush_key(&2);
+        memory.flush_key(&3);
+        assert_eq!(*batches.borrow(), [vec![(2, 4)]]);
+
+        memory.flush();
+        assert_eq!(*batches.borrow(), [vec![(2, 4)], vec![(1, 2)]]);
+    }
+}